@@ -1,32 +1,443 @@
-use crate::Volume;
+use crate::enemy::{Elite, EnemyCount};
+use crate::player::Player;
+use crate::{AppState, GameState, Volume};
+use bevy::audio::{PlaybackMode, SpatialScale, Volume as SinkVolume};
 use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::seq::SliceRandom;
+use rand::Rng;
 pub struct AudioPlugin;
 
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, play_music);
-        app.add_systems(Update, update_music_volume);
-        app.add_systems(Update, update_sfx_volume);
+        app.add_event::<SfxEvent>();
+        app.add_systems(Startup, load_sfx);
+        app.add_systems(Update, (crossfade_music, update_music_volume).chain());
+        app.add_systems(Update, (play_sfx, update_sfx_volume).chain());
+        app.add_systems(Update, attach_listener);
     }
 }
+
+/// Which volume slider a sound answers to
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AudioBus {
+    Music,
+    Sfx,
+    Ui,
+}
+
+impl Volume {
+    pub fn bus(&self, bus: AudioBus) -> f32 {
+        match bus {
+            AudioBus::Music => self.music,
+            AudioBus::Sfx => self.sfx,
+            AudioBus::Ui => self.ui,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Sfx {
+    EnemyHit,
+    PlayerHit,
+    EnemyDeath,
+    Pickup,
+    UiConfirm,
+    /// An elite arriving, so it can be heard coming from off screen
+    EliteSpawn,
+}
+
+pub struct SoundBank {
+    /// One is picked at random each time the sound plays
+    pub clips: &'static [&'static str],
+    /// Playback speed before jitter
+    pub speed: f32,
+    pub bus: AudioBus,
+    pub volume: f32,
+    /// Further requests are dropped while this many copies are playing
+    pub max_concurrent: usize,
+    /// Random +/- applied to playback speed and volume
+    pub pitch_jitter: f32,
+    pub volume_jitter: f32,
+}
+
+impl Sfx {
+    pub const ALL: [Sfx; 6] = [
+        Sfx::EnemyHit,
+        Sfx::PlayerHit,
+        Sfx::EnemyDeath,
+        Sfx::Pickup,
+        Sfx::UiConfirm,
+        Sfx::EliteSpawn,
+    ];
+
+    pub fn bank(self) -> SoundBank {
+        match self {
+            Sfx::EnemyHit => SoundBank {
+                clips: &["sounds/hit1.ogg", "sounds/hit2.ogg"],
+                speed: 1.0,
+                bus: AudioBus::Sfx,
+                volume: 0.7,
+                max_concurrent: 6,
+                pitch_jitter: 0.15,
+                volume_jitter: 0.15,
+            },
+            Sfx::PlayerHit => SoundBank {
+                clips: &["sounds/hit2.ogg"],
+                speed: 1.0,
+                bus: AudioBus::Sfx,
+                volume: 1.0,
+                max_concurrent: 2,
+                pitch_jitter: 0.05,
+                volume_jitter: 0.0,
+            },
+            Sfx::EnemyDeath => SoundBank {
+                clips: &["sounds/snowman_death.ogg"],
+                speed: 1.0,
+                bus: AudioBus::Sfx,
+                volume: 0.8,
+                max_concurrent: 4,
+                pitch_jitter: 0.2,
+                volume_jitter: 0.1,
+            },
+            Sfx::Pickup => SoundBank {
+                clips: &["sounds/powerup.ogg"],
+                speed: 1.0,
+                bus: AudioBus::Sfx,
+                volume: 1.0,
+                max_concurrent: 2,
+                pitch_jitter: 0.05,
+                volume_jitter: 0.0,
+            },
+            Sfx::UiConfirm => SoundBank {
+                clips: &["sounds/powerup.ogg"],
+                speed: 1.0,
+                bus: AudioBus::Ui,
+                volume: 0.6,
+                max_concurrent: 1,
+                pitch_jitter: 0.0,
+                volume_jitter: 0.0,
+            },
+            Sfx::EliteSpawn => SoundBank {
+                clips: &["sounds/snowman_death.ogg"],
+                speed: 0.6,
+                bus: AudioBus::Sfx,
+                volume: 1.0,
+                max_concurrent: 2,
+                pitch_jitter: 0.05,
+                volume_jitter: 0.0,
+            },
+        }
+    }
+}
+
+/// Send this to play a sound; it gets its own entity so it outlives whatever caused it.
+/// Sounds with a position are panned and attenuated relative to the player.
+#[derive(Event)]
+pub struct SfxEvent {
+    pub sound: Sfx,
+    pub position: Option<Vec2>,
+}
+
+impl SfxEvent {
+    pub fn new(sound: Sfx) -> Self {
+        SfxEvent {
+            sound,
+            position: None,
+        }
+    }
+
+    pub fn at(sound: Sfx, position: Vec2) -> Self {
+        SfxEvent {
+            sound,
+            position: Some(position),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct SfxClips(HashMap<Sfx, Vec<Handle<AudioSource>>>);
+
+/// A detached, playing sound effect. `gain` is its volume before the bus is applied.
 #[derive(Component)]
-struct Music;
+struct SfxEmitter {
+    sound: Sfx,
+    bus: AudioBus,
+    gain: f32,
+}
+
+/// Distance between the listener's ears, in world units
+const EAR_GAP: f32 = 24.0;
+/// World units to audio units; a sound this many pixels away is about half as loud
+const SPATIAL_SCALE: f32 = 1.0 / 120.0;
+
+/// Seconds for one track to fade out while the next fades in
+const CROSSFADE_TIME: f32 = 1.5;
+/// Music level while the game is paused or a menu is over it
+const DUCK_VOLUME: f32 = 0.35;
+/// Enemies on screen for a track to reach full intensity
+const HORDE_SIZE: i32 = 100;
+/// Music level of an intense track while the map is empty, rising to full as the horde grows
+const CALM_VOLUME: f32 = 0.7;
+/// How fast intensity follows the enemy count, per second
+const INTENSITY_FADE_SPEED: f32 = 0.5;
+/// Seconds an elite has to survive before the boss track cuts in, so quick kills don't flip the music
+const BOSS_MUSIC_DELAY: f32 = 5.0;
+/// Seconds the boss track keeps playing once no elites are left
+const BOSS_MUSIC_LINGER: f32 = 15.0;
 
-fn play_music(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn((
-        AudioPlayer::new(asset_server.load("sounds/music.ogg")),
-        Music,
-        PlaybackSettings::LOOP,
-    ));
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MusicTrack {
+    Menu,
+    InGame,
+    /// Plays once an elite has stuck around for a while
+    Boss,
+    GameOver,
 }
-fn update_music_volume(q_music: Query<&AudioSink, With<Music>>, volume: Res<Volume>) {
-    let music = q_music.get_single();
-    if let Ok(music) = music {
-        music.set_volume(volume.music);
+
+pub struct TrackDef {
+    pub clip: &'static str,
+    pub speed: f32,
+    /// Extra playback speed at a full horde; tracks with any also swell from `CALM_VOLUME`
+    pub intensity: f32,
+}
+
+impl MusicTrack {
+    pub fn def(self) -> TrackDef {
+        match self {
+            MusicTrack::Menu => TrackDef {
+                clip: "sounds/music.ogg",
+                speed: 0.9,
+                intensity: 0.0,
+            },
+            MusicTrack::InGame => TrackDef {
+                clip: "sounds/music.ogg",
+                speed: 1.0,
+                intensity: 0.08,
+            },
+            MusicTrack::Boss => TrackDef {
+                clip: "sounds/music.ogg",
+                speed: 1.12,
+                intensity: 0.04,
+            },
+            MusicTrack::GameOver => TrackDef {
+                clip: "sounds/music.ogg",
+                speed: 0.75,
+                intensity: 0.0,
+            },
+        }
     }
 }
-fn update_sfx_volume(q_sfx: Query<&AudioSink, Without<Music>>, volume: Res<Volume>) {
-    for sink in q_sfx.iter() {
-        sink.set_volume(volume.sfx);
+
+/// A playing track. `gain` is its crossfade position and `intensity` how close the horde is
+/// to `HORDE_SIZE`, both 0 to 1.
+#[derive(Component)]
+struct Music {
+    track: MusicTrack,
+    gain: f32,
+    intensity: f32,
+}
+
+impl Music {
+    fn speed(&self) -> f32 {
+        let def = self.track.def();
+        def.speed * (1.0 + def.intensity * self.intensity)
+    }
+
+    fn loudness(&self) -> f32 {
+        if self.track.def().intensity > 0.0 {
+            CALM_VOLUME + (1.0 - CALM_VOLUME) * self.intensity
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Tracks how long elites have been around so the boss track doesn't flicker on and off.
+#[derive(Default)]
+struct BossCue {
+    elite_time: f32,
+    linger: f32,
+}
+
+impl BossCue {
+    fn update(&mut self, elite_alive: bool, dt: f32) -> bool {
+        if elite_alive {
+            self.elite_time += dt;
+        } else {
+            self.elite_time = 0.0;
+        }
+        if self.elite_time >= BOSS_MUSIC_DELAY {
+            self.linger = BOSS_MUSIC_LINGER;
+        } else {
+            self.linger = (self.linger - dt).max(0.0);
+        }
+        self.linger > 0.0
+    }
+}
+
+fn wanted_track(app_state: &AppState, boss: bool) -> MusicTrack {
+    match app_state {
+        AppState::InGame if boss => MusicTrack::Boss,
+        AppState::InGame => MusicTrack::InGame,
+        AppState::GameOver => MusicTrack::GameOver,
+        _ => MusicTrack::Menu,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn crossfade_music(
+    mut commands: Commands,
+    mut q_music: Query<(Entity, &mut Music)>,
+    q_elites: Query<(), With<Elite>>,
+    app_state: Res<State<AppState>>,
+    enemy_count: Res<EnemyCount>,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut boss_cue: Local<BossCue>,
+) {
+    if *app_state.get() != AppState::InGame {
+        *boss_cue = BossCue::default();
+    }
+    let boss = boss_cue.update(!q_elites.is_empty(), time.delta_secs());
+    let wanted = wanted_track(app_state.get(), boss);
+    let step = time.delta_secs() / CROSSFADE_TIME;
+    // The track quickens and swells as the horde builds, easing so single spawns don't lurch it
+    let intensity = (enemy_count.enemy_count as f32 / HORDE_SIZE as f32).clamp(0.0, 1.0);
+    let fade = time.delta_secs() * INTENSITY_FADE_SPEED;
+
+    let mut playing = false;
+    for (entity, mut music) in q_music.iter_mut() {
+        music.intensity += (intensity - music.intensity).clamp(-fade, fade);
+        if music.track == wanted {
+            playing = true;
+            music.gain = (music.gain + step).min(1.0);
+        } else {
+            music.gain -= step;
+            if music.gain <= 0.0 {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+    if !playing {
+        let music = Music {
+            track: wanted,
+            gain: 0.0,
+            intensity,
+        };
+        let settings = PlaybackSettings::LOOP
+            .with_volume(SinkVolume::new(0.0))
+            .with_speed(music.speed());
+        commands.spawn((
+            Name::new("music"),
+            AudioPlayer::new(asset_server.load(wanted.def().clip)),
+            settings,
+            music,
+        ));
+    }
+}
+
+fn load_sfx(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let clips = Sfx::ALL
+        .iter()
+        .map(|&sound| {
+            let handles = sound
+                .bank()
+                .clips
+                .iter()
+                .map(|&path| asset_server.load(path))
+                .collect();
+            (sound, handles)
+        })
+        .collect();
+    commands.insert_resource(SfxClips(clips));
+}
+
+fn update_music_volume(
+    q_music: Query<(&Music, &AudioSink)>,
+    app_state: Res<State<AppState>>,
+    game_state: Res<State<GameState>>,
+    volume: Res<Volume>,
+) {
+    // Duck under the pause and upgrade screens
+    let duck = match (app_state.get(), game_state.get()) {
+        (AppState::InGame, GameState::Playing) => 1.0,
+        (AppState::InGame, _) => DUCK_VOLUME,
+        _ => 1.0,
+    };
+    let level = volume.bus(AudioBus::Music) * duck;
+    for (music, sink) in q_music.iter() {
+        sink.set_volume(music.gain * music.loudness() * level);
+        sink.set_speed(music.speed());
+    }
+}
+
+fn play_sfx(
+    mut commands: Commands,
+    mut events: EventReader<SfxEvent>,
+    q_emitters: Query<&SfxEmitter>,
+    clips: Res<SfxClips>,
+    volume: Res<Volume>,
+) {
+    let mut playing: HashMap<Sfx, usize> = HashMap::new();
+    for emitter in q_emitters.iter() {
+        *playing.entry(emitter.sound).or_default() += 1;
+    }
+    let mut rng = rand::thread_rng();
+    for SfxEvent { sound, position } in events.read() {
+        let bank = sound.bank();
+        let count = playing.entry(*sound).or_default();
+        if *count >= bank.max_concurrent {
+            continue;
+        }
+        let Some(clip) = clips.0[sound].choose(&mut rng) else {
+            continue;
+        };
+        *count += 1;
+        let gain = bank.volume * (1.0 + rng.gen_range(-1.0..=1.0) * bank.volume_jitter);
+        let speed = bank.speed * (1.0 + rng.gen_range(-1.0..=1.0) * bank.pitch_jitter);
+        commands.spawn((
+            Name::new("sfx"),
+            SfxEmitter {
+                sound: *sound,
+                bus: bank.bus,
+                gain,
+            },
+            AudioPlayer::new(clip.clone()),
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                volume: SinkVolume::new(gain * volume.bus(bank.bus)),
+                speed,
+                spatial: position.is_some(),
+                spatial_scale: Some(SpatialScale::new_2d(SPATIAL_SCALE)),
+                ..default()
+            },
+            Transform::from_translation(position.unwrap_or_default().extend(0.0)),
+        ));
+    }
+}
+
+fn update_sfx_volume(
+    q_sfx: Query<(&AudioSink, &SfxEmitter)>,
+    q_spatial: Query<(&SpatialAudioSink, &SfxEmitter)>,
+    volume: Res<Volume>,
+) {
+    if !volume.is_changed() {
+        return;
+    }
+    for (sink, emitter) in q_sfx.iter() {
+        sink.set_volume(emitter.gain * volume.bus(emitter.bus));
+    }
+    for (sink, emitter) in q_spatial.iter() {
+        sink.set_volume(emitter.gain * volume.bus(emitter.bus));
+    }
+}
+
+/// The player is the ears for positional sounds
+fn attach_listener(mut commands: Commands, q_player: Query<Entity, Added<Player>>) {
+    for entity in q_player.iter() {
+        commands
+            .entity(entity)
+            .insert(SpatialListener::new(EAR_GAP));
     }
 }
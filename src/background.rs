@@ -96,7 +96,6 @@ fn setup(
     let layout = TextureAtlasLayout::from_grid(UVec2::splat(48), 14, 1, None, None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
 
-    let mut i: i32 = 0;
     for dx in -1..=1 {
         for dy in -1..=1 {
             let pos = Vec3::new((snap_x + dx) as f32 * w, (snap_y + dy) as f32 * h, 0.0);
@@ -144,10 +143,8 @@ fn setup(
                     .entity(background_holder)
                     .add_child(background_prop);
 
-                o = o + 1;
+                o += 1;
             }
-
-            i += 1;
         }
     }
 }
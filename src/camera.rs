@@ -1,25 +1,49 @@
 //! Shows how to create graphics that snap to the pixel grid by rendering to a texture in 2D
 
 use bevy::{
-    prelude::*, render::{
+    prelude::*,
+    render::{
         camera::RenderTarget,
         render_resource::{
             Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
         },
         view::RenderLayers,
-    }, window::WindowResized
+    },
+    window::WindowResized,
 };
 
+use crate::GameState;
+
 #[derive(Resource)]
-pub struct Resolution{
+pub struct Resolution {
     pub width: u32,
     pub height: u32,
-    pub base_width: u32,
-    pub base_height: u32,
 }
 
+/// How far the in-game camera may zoom; each game mode passes its own to `CameraZoomPlugin`.
+#[derive(Resource, Clone, Copy)]
+pub struct ZoomBounds {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl ZoomBounds {
+    pub const SURVIVAL: ZoomBounds = ZoomBounds { min: 1.0, max: 3.0 };
+}
+
+/// The zoom gameplay wants; the camera eases toward it within `ZoomBounds`.
+/// Zooming scales the in-game camera's projection, so the canvas never changes size.
 #[derive(Resource)]
-struct CanvasHandle(Handle<Image>);
+pub struct CameraZoom {
+    pub target: f32,
+}
+
+/// Fraction of the way to the target zoom covered each second
+const ZOOM_SPEED: f32 = 2.0;
+
+/// Distance from the camera to just past the corners of the screen at zoom 1; multiply by
+/// the projection scale for the current view.
+pub const VIEW_RADIUS: f32 = 350.0;
 
 /// In-game resolution width.
 pub const RES_WIDTH: u32 = 480;
@@ -38,15 +62,41 @@ pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Resolution{
+        app.insert_resource(Resolution {
             width: RES_WIDTH,
             height: RES_HEIGHT,
-            base_width: RES_WIDTH,
-            base_height: RES_HEIGHT,
         });
+        app.insert_resource(CameraZoom { target: 1.0 });
         app.add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()));
         app.add_systems(Startup, setup_camera);
-        app.add_systems(Update, (rotate, (update_canvas_size, fit_canvas).chain()));
+        app.add_systems(Update, (rotate, fit_canvas));
+    }
+}
+
+/// Eases the in-game camera's zoom while `state` is active, within that mode's `bounds`.
+/// Every run starts, and every menu shows, at zoom 1.
+pub struct CameraZoomPlugin<S: States> {
+    pub state: S,
+    pub bounds: ZoomBounds,
+}
+
+impl<S: States> Plugin for CameraZoomPlugin<S> {
+    fn build(&self, app: &mut App) {
+        let bounds = self.bounds;
+        app.add_systems(
+            OnEnter(self.state.clone()),
+            (
+                move |mut commands: Commands| commands.insert_resource(bounds),
+                reset_zoom,
+            ),
+        );
+        app.add_systems(
+            Update,
+            smooth_zoom
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Playing)),
+        );
+        app.add_systems(OnExit(self.state.clone()), reset_zoom);
     }
 }
 
@@ -77,30 +127,23 @@ pub struct Rotate {
     pub speed: f32,
 }
 
-fn update_canvas_size(
-    mut images: ResMut<Assets<Image>>,
-    resolution: Res<Resolution>,
-    canvas_handle: Res<CanvasHandle>,
+fn reset_zoom(
+    mut projection: Single<&mut OrthographicProjection, With<InGameCamera>>,
+    mut zoom: ResMut<CameraZoom>,
 ) {
-    if let Some(canvas) = images.get_mut(&canvas_handle.0) {
-        // New canvas size
-        let new_width = resolution.width;
-        let new_height = resolution.height;
-
-        // Update the Extent3d size
-        let new_size = Extent3d {
-            width: new_width,
-            height: new_height,
-            ..canvas.texture_descriptor.size
-        };
-        
-        // Update the texture descriptor and resize the buffer
-        canvas.texture_descriptor.size = new_size;
-        canvas.resize(new_size);
-
-        // // The canvas size has now been updated
-        // info!("Canvas size updated to {}x{}", new_width, new_height);
-    }
+    zoom.target = 1.0;
+    projection.scale = 1.0;
+}
+
+fn smooth_zoom(
+    mut projection: Single<&mut OrthographicProjection, With<InGameCamera>>,
+    zoom: Res<CameraZoom>,
+    bounds: Res<ZoomBounds>,
+    time: Res<Time>,
+) {
+    let target = zoom.target.clamp(bounds.min, bounds.max);
+    let t = (ZOOM_SPEED * time.delta_secs()).min(1.0);
+    projection.scale += (target - projection.scale) * t;
 }
 
 fn setup_camera(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
@@ -132,8 +175,6 @@ fn setup_camera(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
 
     let image_handle = images.add(canvas);
 
-    commands.insert_resource(CanvasHandle(image_handle.clone()));
-
     // this camera renders whatever is on `PIXEL_PERFECT_LAYERS` to the canvas
     commands.spawn((
         Camera2d,
@@ -169,7 +210,7 @@ fn rotate(time: Res<Time>, mut rotates: Query<(&mut Transform, &Rotate), With<Ro
 fn fit_canvas(
     mut resize_events: EventReader<WindowResized>,
     mut projection: Single<&mut OrthographicProjection, With<OuterCamera>>,
-    resolution: Res<Resolution>
+    resolution: Res<Resolution>,
 ) {
     for event in resize_events.read() {
         let h_scale = event.width / resolution.width as f32;
@@ -0,0 +1,300 @@
+use crate::player::PlayerStats;
+use crate::AppState;
+use crate::GameState;
+use bevy::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+const PROGRESS_FILE: &str = "progress.txt";
+
+pub struct CharacterPlugin;
+
+impl Plugin for CharacterPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Progress::load());
+        app.insert_resource(SelectedCharacter(0));
+        app.insert_resource(RunStats::default());
+        app.add_systems(OnEnter(AppState::InGame), reset_run_stats);
+        app.add_systems(
+            Update,
+            tick_run_time
+                .run_if(in_state(AppState::InGame))
+                .run_if(in_state(GameState::Playing)),
+        );
+        app.add_systems(OnExit(AppState::InGame), record_progress);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Weapon {
+    CandyCaneShuriken,
+    /// Starts with this many chestnut shields instead of shurikens
+    ChestnutShields(u32),
+}
+
+#[derive(Clone, Copy)]
+pub enum Passive {
+    None,
+    ChestnutShield,
+    InnateFire(i32),
+    HeavySnowball(f32),
+}
+
+#[derive(Clone, Copy)]
+pub enum Unlock {
+    Always,
+    SurviveSeconds(f32),
+    ReachLevel(u32),
+}
+
+/// Base stats a character changes; anything left as `None` keeps the default.
+#[derive(Clone, Copy)]
+pub struct StatOverrides {
+    pub damage: Option<f32>,
+    pub rate_of_fire: Option<f32>,
+    pub acceleration_rate: Option<f32>,
+    pub shield_damage: Option<f32>,
+    pub dash_charges: Option<u32>,
+}
+
+impl StatOverrides {
+    pub const NONE: Self = Self {
+        damage: None,
+        rate_of_fire: None,
+        acceleration_rate: None,
+        shield_damage: None,
+        dash_charges: None,
+    };
+}
+
+pub struct CharacterDef {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub sprite: &'static str,
+    /// Columns in the sprite sheet, and how many of them make up the run animation
+    pub columns: u32,
+    pub frames: u32,
+    pub stats: StatOverrides,
+    pub weapon: Weapon,
+    pub passive: Passive,
+    pub unlock: Unlock,
+}
+
+pub const CHARACTERS: [CharacterDef; 4] = [
+    CharacterDef {
+        name: "Elf",
+        description: "A balanced rider with candy cane shurikens",
+        sprite: "elf.png",
+        columns: 4,
+        frames: 3,
+        stats: StatOverrides::NONE,
+        weapon: Weapon::CandyCaneShuriken,
+        passive: Passive::None,
+        unlock: Unlock::Always,
+    },
+    CharacterDef {
+        name: "Nutcracker",
+        description: "Slower shurikens, but starts with a chestnut shield",
+        sprite: "nutcracker.png",
+        columns: 4,
+        frames: 3,
+        stats: StatOverrides {
+            rate_of_fire: Some(0.5),
+            ..StatOverrides::NONE
+        },
+        weapon: Weapon::CandyCaneShuriken,
+        passive: Passive::ChestnutShield,
+        unlock: Unlock::ReachLevel(5),
+    },
+    CharacterDef {
+        name: "Ember Elf",
+        description: "Every shuriken has a chance to set enemies alight",
+        sprite: "ember_elf.png",
+        columns: 4,
+        frames: 3,
+        stats: StatOverrides {
+            damage: Some(28.0),
+            ..StatOverrides::NONE
+        },
+        weapon: Weapon::CandyCaneShuriken,
+        passive: Passive::InnateFire(20),
+        unlock: Unlock::SurviveSeconds(180.0),
+    },
+    CharacterDef {
+        name: "Snow Angel",
+        description: "No shurikens, but a ring of chestnuts and a heavy snowball",
+        sprite: "snow_angel.png",
+        columns: 4,
+        frames: 3,
+        stats: StatOverrides {
+            shield_damage: Some(20.0),
+            dash_charges: Some(2),
+            ..StatOverrides::NONE
+        },
+        weapon: Weapon::ChestnutShields(3),
+        passive: Passive::HeavySnowball(15.0),
+        unlock: Unlock::SurviveSeconds(600.0),
+    },
+];
+
+impl CharacterDef {
+    pub fn apply(&self, stats: &mut PlayerStats) {
+        let overrides = &self.stats;
+        if let Some(damage) = overrides.damage {
+            stats.damage = damage;
+        }
+        if let Some(rate_of_fire) = overrides.rate_of_fire {
+            stats.rate_of_fire = rate_of_fire;
+        }
+        if let Some(acceleration_rate) = overrides.acceleration_rate {
+            stats.acceleration_rate = acceleration_rate;
+        }
+        if let Some(shield_damage) = overrides.shield_damage {
+            stats.shield_damage = shield_damage;
+        }
+        if let Some(dash_charges) = overrides.dash_charges {
+            stats.dash_charges = dash_charges;
+        }
+
+        match self.weapon {
+            Weapon::CandyCaneShuriken => stats.shurikens = true,
+            Weapon::ChestnutShields(count) => {
+                stats.shurikens = false;
+                stats.num_shields += count;
+            }
+        }
+
+        match self.passive {
+            Passive::None | Passive::HeavySnowball(_) => {}
+            Passive::ChestnutShield => stats.num_shields += 1,
+            Passive::InnateFire(chance) => stats.fire_chance += chance,
+        }
+    }
+
+    /// Extra snowball mass the character starts a run with.
+    pub fn bonus_mass(&self) -> f32 {
+        match self.passive {
+            Passive::HeavySnowball(mass) => mass,
+            _ => 0.0,
+        }
+    }
+
+    pub fn passive_description(&self) -> String {
+        match self.passive {
+            Passive::None => "None".to_string(),
+            Passive::ChestnutShield => "Starts with a chestnut shield".to_string(),
+            Passive::InnateFire(chance) => format!("{}% innate fire chance", chance),
+            Passive::HeavySnowball(mass) => format!("Starts with {} extra snowball mass", mass),
+        }
+    }
+
+    pub fn unlock_description(&self) -> String {
+        match self.unlock {
+            Unlock::Always => "Unlocked".to_string(),
+            Unlock::SurviveSeconds(secs) => format!("Survive {} minutes", secs / 60.0),
+            Unlock::ReachLevel(level) => format!("Reach level {}", level),
+        }
+    }
+
+    pub fn is_unlocked(&self, progress: &Progress) -> bool {
+        match self.unlock {
+            Unlock::Always => true,
+            Unlock::SurviveSeconds(secs) => progress.best_time >= secs,
+            Unlock::ReachLevel(level) => progress.best_level >= level,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct SelectedCharacter(pub usize);
+
+impl SelectedCharacter {
+    pub fn def(&self) -> &'static CharacterDef {
+        &CHARACTERS[self.0]
+    }
+}
+
+/// Best results across all runs, persisted to disk to unlock characters.
+#[derive(Resource, Default)]
+pub struct Progress {
+    pub best_time: f32,
+    pub best_level: u32,
+}
+
+impl Progress {
+    /// Kept next to the executable so it doesn't depend on where the game was launched from.
+    fn path() -> PathBuf {
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|dir| dir.join(PROGRESS_FILE)))
+            .unwrap_or_else(|| PathBuf::from(PROGRESS_FILE))
+    }
+
+    fn load() -> Self {
+        let mut progress = Self::default();
+        let Ok(contents) = fs::read_to_string(Self::path()) else {
+            return progress;
+        };
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            match key.trim() {
+                "best_time" => progress.best_time = value.trim().parse().unwrap_or(0.0),
+                "best_level" => progress.best_level = value.trim().parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+        progress
+    }
+
+    fn save(&self) {
+        let contents = format!(
+            "best_time={}\nbest_level={}\n",
+            self.best_time, self.best_level
+        );
+        if let Err(err) = fs::write(Self::path(), contents) {
+            warn!("Failed to save progress: {}", err);
+        }
+    }
+}
+
+/// Stats for the current run.
+#[derive(Resource)]
+pub struct RunStats {
+    pub time: f32,
+    pub level: u32,
+    pub kills: u32,
+    pub chests_opened: u32,
+    /// Every upgrade id a chest has handed out, in order
+    pub chest_rewards: Vec<u32>,
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            level: 1,
+            kills: 0,
+            chests_opened: 0,
+            chest_rewards: Vec::new(),
+        }
+    }
+}
+
+fn reset_run_stats(mut run_stats: ResMut<RunStats>) {
+    *run_stats = RunStats::default();
+}
+
+fn tick_run_time(mut run_stats: ResMut<RunStats>, time: Res<Time>) {
+    run_stats.time += time.delta_secs();
+}
+
+fn record_progress(run_stats: Res<RunStats>, mut progress: ResMut<Progress>) {
+    if run_stats.time <= progress.best_time && run_stats.level <= progress.best_level {
+        return;
+    }
+    progress.best_time = progress.best_time.max(run_stats.time);
+    progress.best_level = progress.best_level.max(run_stats.level);
+    progress.save();
+}
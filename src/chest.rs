@@ -0,0 +1,103 @@
+use crate::GameState;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use rand::Rng;
+
+const BURST_LIFETIME: f32 = 1.2;
+const BURST_SPARKS: usize = 120;
+const SPARK_DRAG: f32 = 3.0;
+const SPARK_RADIUS: f32 = 1.5;
+
+pub struct ChestPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for ChestPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RevealBurst>();
+        app.insert_resource(RevealSparks::default());
+        app.add_systems(
+            Update,
+            (spawn_reveal_bursts, draw_reveal_sparks)
+                .chain()
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Chest)),
+        );
+        app.add_systems(OnExit(GameState::Chest), clean_up_reveal_bursts);
+    }
+}
+
+/// Fired by the chest screen each time a reward lands, from the middle of its slot and in
+/// the colour of its rarity.
+#[derive(Event)]
+pub struct RevealBurst {
+    pub origin: egui::Pos2,
+    pub color: egui::Color32,
+}
+
+struct Spark {
+    position: egui::Pos2,
+    velocity: egui::Vec2,
+    color: egui::Color32,
+    age: f32,
+}
+
+impl Spark {
+    /// White-hot at first, then the reward's colour fading out.
+    fn color(&self) -> egui::Color32 {
+        let t = self.age / BURST_LIFETIME;
+        if t < 0.3 {
+            egui::Color32::WHITE.lerp_to_gamma(self.color, t / 0.3)
+        } else {
+            self.color.gamma_multiply(1.0 - (t - 0.3) / 0.7)
+        }
+    }
+}
+
+/// Sparks are painted by egui rather than spawned in the world, so they show on top of the
+/// chest screen's overlay instead of being dimmed by it.
+#[derive(Resource, Default)]
+struct RevealSparks(Vec<Spark>);
+
+fn spawn_reveal_bursts(mut bursts: EventReader<RevealBurst>, mut sparks: ResMut<RevealSparks>) {
+    let mut rng = rand::thread_rng();
+    for burst in bursts.read() {
+        for _ in 0..BURST_SPARKS {
+            let offset = egui::Vec2::angled(rng.gen_range(0.0..std::f32::consts::TAU));
+            sparks.0.push(Spark {
+                position: burst.origin + offset * rng.gen_range(0.0..4.0),
+                velocity: offset * rng.gen_range(60.0..160.0),
+                color: burst.color,
+                age: 0.0,
+            });
+        }
+    }
+}
+
+fn draw_reveal_sparks(
+    mut egui_ctx: EguiContexts,
+    mut sparks: ResMut<RevealSparks>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    sparks.0.retain_mut(|spark| {
+        spark.age += dt;
+        spark.position += spark.velocity * dt;
+        spark.velocity *= (1.0 - SPARK_DRAG * dt).max(0.0);
+        spark.age < BURST_LIFETIME
+    });
+    if sparks.0.is_empty() {
+        return;
+    }
+    let painter = egui_ctx.ctx_mut().layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("chest_sparks"),
+    ));
+    for spark in &sparks.0 {
+        painter.circle_filled(spark.position, SPARK_RADIUS, spark.color());
+    }
+}
+
+fn clean_up_reveal_bursts(mut sparks: ResMut<RevealSparks>) {
+    sparks.0.clear();
+}
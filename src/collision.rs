@@ -1,16 +1,26 @@
+use crate::audio::{Sfx, SfxEvent};
+use crate::damage_numbers::DamageEvent;
+use crate::damage_numbers::DamageKind;
+use crate::dash::FreezeTrail;
 use crate::enemy::Enemy;
 use crate::enemy::EnemyCount;
 use crate::enemy::EnemyHealth;
 use crate::enemy::EnemyTimer;
+use crate::enemy::EnemyVisual;
 use crate::enemy::EnemyXp;
-use crate::enemy::Frozen;
-use crate::enemy::OnFire;
-use crate::enemy::Vunerable;
+use crate::evolution::Evolution;
+use crate::juice::Impact;
+use crate::physics::KinematicBody;
+use crate::physics::KNOCKBACK_IMPULSE;
 use crate::pickup::Pickup;
-use crate::player::AnimationIndices;
-use crate::player::AnimationTimer;
+use crate::pickup::PickupCollected;
+use crate::pickup::XpBoost;
 use crate::player::PlayerStats;
-use crate::utils::YSort;
+use crate::snowball::SnowballMass;
+use crate::status::ApplyStatus;
+use crate::status::StatusEffects;
+use crate::status::StatusKind;
+use crate::status::StatusSource;
 use rand::Rng;
 
 use crate::player::Player;
@@ -21,41 +31,36 @@ use crate::player::PoweredUp;
 use crate::player::Projectile;
 use crate::player::Shield;
 use crate::GameState;
-use bevy::audio::PlaybackMode;
 use bevy::prelude::*;
 
 const IFRAME_DURATION: f32 = 0.1;
 const FLASH_DURATION: f32 = 0.1;
-const KNOCKBACK_STRENGTH: f32 = 4.0;
-const FRICTION: f32 = 0.2;
+const SHATTER_RADIUS: f32 = 32.0;
+/// Share of the critical hit dealt to everything around a shattered enemy.
+const SHATTER_DAMAGE_FRACTION: f32 = 0.5;
 
 pub struct CollisionPlugin<S: States> {
     pub state: S,
 }
 
+/// A crit landed on a frozen enemy; the ice breaks and hurts everything nearby.
 #[derive(Component)]
-struct Knockback {
-    direction: Vec2,
-    strength: f32,
+struct Shatter {
+    damage: f32,
 }
 
-#[derive(Component)]
-struct CheckIfFreeze;
-#[derive(Component)]
-struct CheckIfFire;
-
 impl<S: States> Plugin for CollisionPlugin<S> {
     fn build(&self, app: &mut App) {
         app.add_systems(
             FixedUpdate,
             (
-                (projectiles_collision, fire_check, freeze_check).chain(),
+                (projectiles_collision, shatter).chain(),
                 enemy_collision,
-                knockback_system,
                 shield_collision,
                 player_collision,
                 xp_collision,
                 pickup_colliisions,
+                freeze_trail_collision,
             )
                 .run_if(in_state(self.state.clone()))
                 .run_if(in_state(GameState::Playing)),
@@ -70,25 +75,39 @@ impl<S: States> Plugin for CollisionPlugin<S> {
     }
 }
 
-fn knockback_system(
-    mut q: Query<(&mut Transform, &mut Knockback, Entity)>,
-    mut commands: Commands,
+fn roll_crit(stats: &PlayerStats) -> bool {
+    stats.crit_chance >= rand::thread_rng().gen_range(1..100)
+}
+/// Rolls every element's chance for a hit; fire goes before freeze so both on one hit flash freezes.
+fn roll_effects(
+    apply_status: &mut EventWriter<ApplyStatus>,
+    target: Entity,
+    stats: &PlayerStats,
+    source: StatusSource,
 ) {
-    for (mut tf, mut knockback, entity) in q.iter_mut() {
-        let velocity = knockback.direction * knockback.strength;
-        tf.translation += velocity.extend(0.0);
-
-        knockback.strength = (knockback.strength - FRICTION).max(0.0);
-
-        if knockback.strength <= 0.0 {
-            commands.entity(entity).remove::<Knockback>();
+    let chances = [
+        (stats.poison_chance, StatusKind::Poisoned),
+        (stats.chill_chance, StatusKind::Chilled),
+        (stats.fire_chance, StatusKind::Burning),
+        (stats.freeze_chance, StatusKind::Frozen),
+        (stats.shock_chance, StatusKind::Shocked),
+    ];
+    for (chance, kind) in chances {
+        if chance >= rand::thread_rng().gen_range(1..100) {
+            apply_status.send(ApplyStatus {
+                target,
+                kind,
+                source,
+            });
         }
     }
 }
+
 fn xp_collision(
     mut commands: Commands,
     xp_q: Query<(&Transform, Entity, &EnemyXp), Without<Player>>,
     mut player_q: Query<(&Transform, &mut PlayerXp), With<Player>>,
+    xp_boost: Res<XpBoost>,
 ) {
     let (player_tf, mut player_xp) = player_q.single_mut();
     for (xp_tf, xp_entity, xp) in xp_q.iter() {
@@ -96,127 +115,185 @@ fn xp_collision(
         let pos2 = player_tf.translation.truncate();
         let dist = pos1.distance(pos2);
         if dist < 16.0 {
-            player_xp.xp += xp.xp;
+            player_xp.xp += xp.xp * xp_boost.multiplier();
             //println!("Player XP: {}", player_xp.xp);
             commands.entity(xp_entity).despawn_recursive();
         }
     }
 }
+#[allow(clippy::type_complexity)]
 fn projectiles_collision(
     mut commands: Commands,
-    mut projectiles_q: Query<
-        (Entity, &Transform, &mut Projectile, Option<&Vunerable>),
-        Without<Enemy>,
-    >,
+    mut projectiles_q: Query<(Entity, &Transform, &mut Projectile), Without<Enemy>>,
     mut enemies_q: Query<
-        (&mut EnemyHealth, &Transform, Entity, &Children),
+        (
+            &mut EnemyHealth,
+            &Transform,
+            &mut KinematicBody,
+            Entity,
+            &EnemyVisual,
+            &mut StatusEffects,
+        ),
         (With<Enemy>, Without<InvincibleTimer>),
     >,
-    asset_server: Res<AssetServer>,
+    mut sfx: EventWriter<SfxEvent>,
     stats: Res<PlayerStats>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut apply_status: EventWriter<ApplyStatus>,
 ) {
-    for (projectile_entity, projectile_tf, mut projectile, vunerable) in projectiles_q.iter_mut() {
-        for (mut health, enemy_tf, enemy_entity, enemy_children) in enemies_q.iter_mut() {
+    for (projectile_entity, projectile_tf, mut projectile) in projectiles_q.iter_mut() {
+        for (mut health, enemy_tf, mut enemy_body, enemy_entity, enemy_visual, mut statuses) in
+            enemies_q.iter_mut()
+        {
             let pos1 = projectile_tf.translation.truncate();
             let pos2 = enemy_tf.translation.truncate();
             let dist = pos1.distance(pos2);
             if dist < 16.0 {
                 let mut multiplier: f32 = 1.0;
-                if let Some(_vunerable) = vunerable {
-                    multiplier = _vunerable.multiplier;
+                let mut kind = DamageKind::Normal;
+                if let Some(vulnerable) = statuses.get(StatusKind::Vulnerable) {
+                    multiplier = vulnerable.potency;
+                    kind = DamageKind::Vulnerable;
+                }
+                if roll_crit(&stats) {
+                    multiplier *= stats.crit_multiplier;
+                    kind = DamageKind::Crit;
+                    if statuses.has(StatusKind::Frozen) {
+                        statuses.expire(StatusKind::Frozen);
+                        commands.entity(enemy_entity).insert(Shatter {
+                            damage: stats.damage * multiplier,
+                        });
+                    }
                 }
-                health.health -= stats.damage * multiplier;
+                let damage = stats.damage * multiplier;
+                health.health -= damage;
+                damage_events.send(DamageEvent {
+                    target: enemy_entity,
+                    position: pos2,
+                    amount: damage,
+                    kind,
+                    over_time: false,
+                });
                 //println!("enemy destroyed");
                 // Apply knockback to enemy
                 let knockback_direction = (pos2 - pos1).normalize();
-                commands.entity(enemy_entity).insert(Knockback {
-                    direction: knockback_direction,
-                    strength: stats.knockback_strength,
-                });
+                enemy_body.apply_impulse(
+                    knockback_direction * stats.knockback_strength * KNOCKBACK_IMPULSE,
+                );
 
                 if projectile.pierce_amount > 0 {
-                    projectile.pierce_amount = projectile.pierce_amount - 1;
+                    projectile.pierce_amount -= 1;
                 } else {
                     commands.entity(projectile_entity).despawn_recursive();
                 }
 
-                commands.entity(enemy_children[1]).insert(FlashingTimer {
+                commands.entity(enemy_visual.0).insert(FlashingTimer {
                     time_left: FLASH_DURATION,
                     color: Color::srgba(12., 12., 12., 1.),
                 });
                 commands.entity(enemy_entity).insert(InvincibleTimer {
                     time_left: IFRAME_DURATION,
                 });
-                commands.entity(enemy_entity).insert((
-                    AudioPlayer::new(asset_server.load("sounds/hit1.ogg")),
-                    PlaybackSettings {
-                        mode: PlaybackMode::Remove,
-                        ..default()
-                    },
-                ));
-                let random_freeze_chance = rand::thread_rng().gen_range(1..100);
-                if stats.freeze_chance >= random_freeze_chance {
-                    commands.entity(enemy_entity).insert(CheckIfFreeze);
-                }
-
-                let random_burn_chance = rand::thread_rng().gen_range(1..100);
-                if stats.fire_chance >= random_burn_chance {
-                    commands.entity(enemy_entity).insert(CheckIfFire);
+                sfx.send(SfxEvent::at(Sfx::EnemyHit, pos2));
+                roll_effects(
+                    &mut apply_status,
+                    enemy_entity,
+                    &stats,
+                    StatusSource::Projectile,
+                );
+                if stats.has_evolution(Evolution::FrostbiteShurikens) {
+                    apply_status.send(ApplyStatus {
+                        target: enemy_entity,
+                        kind: StatusKind::Chilled,
+                        source: StatusSource::Projectile,
+                    });
                 }
             }
         }
     }
 }
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn shield_collision(
     mut commands: Commands,
     q_player: Query<&Transform, With<Player>>,
-    q_shield: Query<(&GlobalTransform, &Shield), With<Shield>>,
+    q_shield: Query<&GlobalTransform, With<Shield>>,
     mut q_enemy: Query<
-        (&Transform, &mut EnemyHealth, Entity, &Children),
+        (
+            &Transform,
+            &mut EnemyHealth,
+            &mut KinematicBody,
+            Entity,
+            &EnemyVisual,
+            &mut StatusEffects,
+        ),
         (With<Enemy>, Without<Shield>, Without<InvincibleTimer>),
     >,
-    asset_server: Res<AssetServer>,
+    mut sfx: EventWriter<SfxEvent>,
     player_stats: Res<PlayerStats>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut apply_status: EventWriter<ApplyStatus>,
 ) {
     let Ok(player_tf) = q_player.get_single() else {
         return;
     };
-    for (shield_tf, shield) in q_shield.iter() {
-        for (enemy_tf, mut enemy_health, enemy_entity, enemy_children) in q_enemy.iter_mut() {
+    for shield_tf in q_shield.iter() {
+        for (
+            enemy_tf,
+            mut enemy_health,
+            mut enemy_body,
+            enemy_entity,
+            enemy_visual,
+            mut statuses,
+        ) in q_enemy.iter_mut()
+        {
             let pos1 = shield_tf.translation().truncate();
             let pos2 = enemy_tf.translation.truncate();
             let dist = pos1.distance(pos2);
             if dist < 16.0 {
-                enemy_health.health -= player_stats.shield_damage;
+                let mut damage = player_stats.shield_damage;
+                let mut kind = DamageKind::Normal;
+                if roll_crit(&player_stats) {
+                    damage *= player_stats.crit_multiplier;
+                    kind = DamageKind::Crit;
+                    if statuses.has(StatusKind::Frozen) {
+                        statuses.expire(StatusKind::Frozen);
+                        commands.entity(enemy_entity).insert(Shatter { damage });
+                    }
+                }
+                enemy_health.health -= damage;
+                damage_events.send(DamageEvent {
+                    target: enemy_entity,
+                    position: pos2,
+                    amount: damage,
+                    kind,
+                    over_time: false,
+                });
                 let knockback_direction = (pos2 - player_tf.translation.truncate()).normalize();
-                commands.entity(enemy_children[1]).insert(FlashingTimer {
+                commands.entity(enemy_visual.0).insert(FlashingTimer {
                     time_left: FLASH_DURATION,
                     color: Color::srgba(12., 12., 12., 1.),
                 });
                 commands.entity(enemy_entity).insert(InvincibleTimer {
                     time_left: IFRAME_DURATION,
                 });
-                commands.entity(enemy_entity).insert(Knockback {
-                    direction: knockback_direction,
-                    strength: player_stats.knockback_strength,
-                });
-                commands.entity(enemy_entity).insert((
-                    AudioPlayer::new(asset_server.load("sounds/hit1.ogg")),
-                    PlaybackSettings {
-                        mode: PlaybackMode::Remove,
-                        ..default()
-                    },
-                ));
-                let random_freeze_chance = rand::thread_rng().gen_range(1..100);
+                enemy_body.apply_impulse(
+                    knockback_direction * player_stats.knockback_strength * KNOCKBACK_IMPULSE,
+                );
+                sfx.send(SfxEvent::at(Sfx::EnemyHit, pos2));
                 if player_stats.shield_apply_effects {
-                    if player_stats.freeze_chance >= random_freeze_chance {
-                        commands.entity(enemy_entity).insert(CheckIfFreeze);
-                    }
-
-                    let random_burn_chance = rand::thread_rng().gen_range(1..100);
-                    if player_stats.fire_chance >= random_burn_chance {
-                        commands.entity(enemy_entity).insert(CheckIfFire);
-                    }
+                    roll_effects(
+                        &mut apply_status,
+                        enemy_entity,
+                        &player_stats,
+                        StatusSource::Shield,
+                    );
+                }
+                if player_stats.has_evolution(Evolution::RoastedChestnutRing) {
+                    apply_status.send(ApplyStatus {
+                        target: enemy_entity,
+                        kind: StatusKind::Burning,
+                        source: StatusSource::Shield,
+                    });
                 }
             }
         }
@@ -242,71 +319,96 @@ fn enemy_collision(mut q: Query<&mut Transform, With<Enemy>>) {
         }
     }
 }
+#[allow(clippy::type_complexity)]
+fn freeze_trail_collision(
+    q_trail: Query<&Transform, With<FreezeTrail>>,
+    q_enemy: Query<(&Transform, Entity, &StatusEffects), (With<Enemy>, Without<FreezeTrail>)>,
+    mut apply_status: EventWriter<ApplyStatus>,
+) {
+    for (enemy_tf, enemy_entity, statuses) in q_enemy.iter() {
+        if statuses.has(StatusKind::Frozen) {
+            continue;
+        }
+        let pos = enemy_tf.translation.truncate();
+        if q_trail
+            .iter()
+            .any(|trail_tf| trail_tf.translation.truncate().distance(pos) < 16.0)
+        {
+            apply_status.send(ApplyStatus {
+                target: enemy_entity,
+                kind: StatusKind::Frozen,
+                source: StatusSource::FreezeTrail,
+            });
+        }
+    }
+}
 fn pickup_colliisions(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    q_pickup: Query<(&Transform, Entity), (With<Pickup>, Without<Player>)>,
-    q_player: Query<(&Transform, Entity), With<Player>>,
+    q_pickup: Query<(&Transform, Entity, &Pickup), Without<Player>>,
+    q_player: Query<&Transform, With<Player>>,
+    mut collected: EventWriter<PickupCollected>,
 ) {
-    let (player_tf, player_entity) = q_player.single();
-    for (pickup_tf, pickup_entity) in q_pickup.iter() {
+    let player_tf = q_player.single();
+    for (pickup_tf, pickup_entity, pickup) in q_pickup.iter() {
         let pos1 = pickup_tf.translation.truncate();
         let pos2 = player_tf.translation.truncate();
         let dist = pos1.distance(pos2);
         if dist < 16.0 {
             commands.entity(pickup_entity).despawn_recursive();
-            commands.entity(player_entity).insert(PoweredUp {
-                timer: Timer::from_seconds(5.0, TimerMode::Once),
-            });
-            commands.entity(player_entity).insert(Blink {
-                color: Color::srgba(0., 1., 0., 1.),
-                speed: 1.0,
-            });
-            commands.entity(player_entity).insert((
-                AudioPlayer::new(asset_server.load("sounds/powerup.ogg")),
-                PlaybackSettings {
-                    mode: PlaybackMode::Remove,
-                    ..default()
-                },
-            ));
+            collected.send(PickupCollected { kind: pickup.kind });
         }
     }
 }
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn player_collision(
     mut commands: Commands,
     mut q_player: Query<
         (
             &mut PlayerHealth,
             Entity,
-            &mut Player,
+            &mut KinematicBody,
+            &SnowballMass,
             Option<&InvincibleTimer>,
         ),
-        Without<PlayerSnowball>,
+        (With<Player>, Without<PlayerSnowball>, Without<Enemy>),
     >,
     mut q_player_snowball: Query<&mut GlobalTransform, With<PlayerSnowball>>,
     q_player_poweredup: Query<Entity, (With<PoweredUp>, Without<PlayerSnowball>)>,
-    mut q_enemy: Query<(&mut Transform, &mut EnemyHealth, Entity), With<Enemy>>,
-    asset_server: Res<AssetServer>,
+    mut q_enemy: Query<
+        (
+            &mut Transform,
+            &mut EnemyHealth,
+            &mut KinematicBody,
+            Entity,
+            &mut StatusEffects,
+        ),
+        With<Enemy>,
+    >,
+    mut sfx: EventWriter<SfxEvent>,
     mut enemy_count: ResMut<EnemyCount>,
     player_stats: Res<PlayerStats>,
     enemy_time: Res<EnemyTimer>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut impacts: EventWriter<Impact>,
 ) {
-    let Ok((mut player_health, player_entity, mut player, iframes)) = q_player.get_single_mut()
+    let Ok((mut player_health, player_entity, mut player_body, snowball, iframes)) =
+        q_player.get_single_mut()
     else {
         return;
     };
     let player_snowball_tf = q_player_snowball.single_mut();
-    for (mut enemy_tf, mut enemy_health, enemy_entity) in q_enemy.iter_mut() {
+    for (mut enemy_tf, mut enemy_health, mut enemy_body, enemy_entity, mut statuses) in
+        q_enemy.iter_mut()
+    {
         let pos1 = player_snowball_tf.translation().truncate();
         let pos2 = enemy_tf.translation.truncate();
         let dist = pos1.distance(pos2);
-        //let collision_radius = 12.0 * player_snowball_tf.scale().x;
-        let collision_radius = 10.0 * player_snowball_tf.scale().x; //Made a little smaller to forgive players
+        let collision_radius = snowball.collision_radius();
         let max_collision_radius: f32 = 500.;
         let player_poweredup: bool = q_player_poweredup.get_single().is_ok();
 
-        if (dist > max_collision_radius) {
-            if (enemy_time.next_enemy_reached) {
+        if dist > max_collision_radius {
+            if enemy_time.next_enemy_reached {
                 enemy_tf.translation = ((pos1 * 2.) - pos2).extend(0.);
                 commands
                     .entity(enemy_entity)
@@ -321,18 +423,12 @@ fn player_collision(
         if !player_poweredup {
             if dist < collision_radius {
                 let collision_direction = (pos2 - pos1).normalize();
-                if let Some(iframes) = iframes {
-                } else {
+                if iframes.is_none() {
                     player_health.hp -= 3.0;
-                    player.velocity = player.velocity * 0.5;
+                    player_body.velocity *= 0.5;
 
-                    commands.entity(player_entity).insert((
-                        AudioPlayer::new(asset_server.load("sounds/hit2.ogg")),
-                        PlaybackSettings {
-                            mode: PlaybackMode::Remove,
-                            ..default()
-                        },
-                    ));
+                    sfx.send(SfxEvent::new(Sfx::PlayerHit));
+                    impacts.send(Impact::PLAYER_HIT);
 
                     commands.entity(player_entity).insert(FlashingTimer {
                         time_left: FLASH_DURATION,
@@ -342,19 +438,34 @@ fn player_collision(
                         time_left: IFRAME_DURATION,
                     });
                 }
-                commands.entity(enemy_entity).insert(Knockback {
-                    direction: collision_direction,
-                    strength: player_stats.knockback_strength,
-                });
+                enemy_body.apply_impulse(
+                    collision_direction * player_stats.knockback_strength * KNOCKBACK_IMPULSE,
+                );
             }
         } else {
             if dist < collision_radius {
                 let collision_direction = (pos2 - pos1).normalize();
-                enemy_health.health -= player_stats.snowball_damage_multiplier * 25.;
-                commands.entity(enemy_entity).insert(Knockback {
-                    direction: collision_direction,
-                    strength: player_stats.knockback_strength * 2.0,
+                let mut damage = player_stats.snowball_damage_multiplier * 25.;
+                let mut kind = DamageKind::Normal;
+                if roll_crit(&player_stats) {
+                    damage *= player_stats.crit_multiplier;
+                    kind = DamageKind::Crit;
+                    if statuses.has(StatusKind::Frozen) {
+                        statuses.expire(StatusKind::Frozen);
+                        commands.entity(enemy_entity).insert(Shatter { damage });
+                    }
+                }
+                enemy_health.health -= damage;
+                damage_events.send(DamageEvent {
+                    target: enemy_entity,
+                    position: pos2,
+                    amount: damage,
+                    kind,
+                    over_time: false,
                 });
+                enemy_body.apply_impulse(
+                    collision_direction * player_stats.knockback_strength * 2.0 * KNOCKBACK_IMPULSE,
+                );
             }
         }
     }
@@ -367,11 +478,11 @@ pub struct FlashingTimer {
 }
 
 #[derive(Component)]
-struct InvincibleTimer {
-    time_left: f32,
+pub struct InvincibleTimer {
+    pub time_left: f32,
 }
 
-fn flashing(
+pub(crate) fn flashing(
     mut commands: Commands,
     mut flashing_query: Query<(&mut FlashingTimer, Entity, &mut Sprite)>,
     time: Res<Time>,
@@ -398,7 +509,7 @@ pub struct DestroyAfter {
     pub duration: f32,
 }
 
-fn blinking(mut blinking_query: Query<(&Blink, &mut Sprite)>, time: Res<Time>) {
+pub(crate) fn blinking(mut blinking_query: Query<(&Blink, &mut Sprite)>, time: Res<Time>) {
     for (blink, mut blink_sprite) in blinking_query.iter_mut() {
         let sin = 0.5 * ((time.elapsed_secs() * blink.speed * std::f32::consts::PI).sin() + 1.);
         let srgba: Srgba = blink.color.into();
@@ -444,164 +555,31 @@ fn destroy_after(
     }
 }
 
-fn freeze_check(
+/// The frozen status itself is expired by the hit; this deals the splash damage.
+fn shatter(
     mut commands: Commands,
-    mut q_entity: Query<(
-        &CheckIfFreeze,
-        &mut EnemyHealth,
-        Entity,
-        &Children,
-        Option<&Frozen>,
-        Option<&OnFire>,
-        Option<&CheckIfFire>,
-    )>,
-    fire_query: Query<(&OnFire, Entity, Option<&DestroyAfter>)>,
-    stats: Res<PlayerStats>,
-    asset_server: Res<AssetServer>,
+    q_shatter: Query<(Entity, &Transform, &Shatter)>,
+    mut q_enemy: Query<(Entity, &Transform, &mut EnemyHealth), With<Enemy>>,
+    mut damage_events: EventWriter<DamageEvent>,
 ) {
-    for (
-        _check_freeze,
-        mut enemy_health,
-        enemy_entity,
-        enemy_children,
-        frozen,
-        on_fire,
-        fire_check,
-    ) in q_entity.iter_mut()
-    {
-        if let Some(frozen) = frozen {
-            commands.entity(enemy_entity).remove::<CheckIfFreeze>();
-        } else {
-            // Check if on fire
-            if let Some(_on_fire) = on_fire {
-                if let Some(_fire_check) = fire_check {
-                    commands.entity(enemy_entity).remove::<CheckIfFire>();
-                }
-
-                if stats.flash_freeze {
-                    enemy_health.health -= enemy_health.health * stats.flash_freeze_percent_damage;
-                }
-
-                commands.entity(enemy_entity).remove::<OnFire>();
-                commands.entity(enemy_children[1]).remove::<Blink>();
-                commands.entity(enemy_children[1]).insert(FlashingTimer {
-                    time_left: 0.0,
-                    color: Color::srgba(1., 1., 1., 1.),
-                });
-                for (_child, child_entity, to_destroy) in fire_query.iter_many(enemy_children) {
-                    commands.entity(child_entity).remove::<OnFire>();
-
-                    if let Some(_to_destroy) = to_destroy {
-                        commands.entity(child_entity).despawn_recursive();
-                    }
-                }
+    for (shatter_entity, shatter_tf, shatter) in q_shatter.iter() {
+        commands.entity(shatter_entity).remove::<Shatter>();
+
+        let center = shatter_tf.translation.truncate();
+        let damage = shatter.damage * SHATTER_DAMAGE_FRACTION;
+        for (enemy_entity, enemy_tf, mut enemy_health) in q_enemy.iter_mut() {
+            let pos = enemy_tf.translation.truncate();
+            if enemy_entity == shatter_entity || pos.distance(center) > SHATTER_RADIUS {
+                continue;
             }
-            ////////////////////////////////////////////////
-
-            commands.entity(enemy_entity).insert(Frozen {
-                duration: stats.freeze_duration,
-            });
-            commands.entity(enemy_children[1]).insert(Frozen {
-                duration: stats.freeze_duration,
+            enemy_health.health -= damage;
+            damage_events.send(DamageEvent {
+                target: enemy_entity,
+                position: pos,
+                amount: damage,
+                kind: DamageKind::Freeze,
+                over_time: false,
             });
-
-            let freeze_sprite = commands
-                .spawn((
-                    Sprite::from_image(asset_server.load("Freeze.png")),
-                    YSort { z: 0.6 },
-                    Frozen {
-                        duration: stats.freeze_duration,
-                    },
-                    DestroyAfter {
-                        duration: stats.freeze_duration,
-                    },
-                ))
-                .id();
-
-            commands.entity(enemy_entity).add_child(freeze_sprite);
-        }
-    }
-}
-
-fn fire_check(
-    mut commands: Commands,
-    mut q_entity: Query<(
-        &CheckIfFire,
-        Entity,
-        &Children,
-        Option<&OnFire>,
-        Option<&Frozen>,
-        Option<&CheckIfFreeze>,
-    )>,
-    frozen_query: Query<(&Frozen, Entity, Option<&DestroyAfter>)>,
-    stats: Res<PlayerStats>,
-    asset_server: Res<AssetServer>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-) {
-    for (_check_fire, enemy_entity, enemy_children, on_fire, frozen, freeze_check) in
-        q_entity.iter_mut()
-    {
-        if let Some(on_fire) = on_fire {
-            commands.entity(enemy_entity).remove::<CheckIfFire>();
-        } else {
-            //Check if frozen
-            if let Some(_frozen) = frozen {
-                for (_child, child_entity, to_destroy) in frozen_query.iter_many(enemy_children) {
-                    commands.entity(child_entity).remove::<Frozen>();
-
-                    if let Some(_to_destroy) = to_destroy {
-                        commands.entity(child_entity).despawn_recursive();
-                    }
-                }
-
-                commands.entity(enemy_entity).remove::<Frozen>();
-                if let Some(_freeze_check) = freeze_check {
-                    commands.entity(enemy_entity).remove::<CheckIfFreeze>();
-                }
-
-                if stats.freezer_burn {
-                    commands.entity(enemy_entity).insert(Vunerable {
-                        duration: stats.freezer_burn_duration,
-                        multiplier: stats.freezer_burn_multiplier,
-                    });
-                }
-            }
-            //////////////////////////////////////////////
-
-            commands.entity(enemy_entity).insert(OnFire {
-                duration: stats.fire_duration,
-            });
-            commands.entity(enemy_children[1]).insert(Blink {
-                color: Color::srgba(12., 12., 12., 1.),
-                speed: 10.0,
-            });
-
-            let texture = asset_server.load("fire.png");
-            let layout = TextureAtlasLayout::from_grid(UVec2::splat(36), 2, 1, None, None);
-            let texture_atlas_layout = texture_atlas_layouts.add(layout);
-            let animation_indices = AnimationIndices { first: 0, last: 1 };
-            let fire_sprite = commands
-                .spawn((
-                    YSort { z: 0.6 },
-                    OnFire {
-                        duration: stats.fire_duration,
-                    },
-                    DestroyAfter {
-                        duration: stats.fire_duration,
-                    },
-                    Sprite::from_atlas_image(
-                        texture,
-                        TextureAtlas {
-                            layout: texture_atlas_layout,
-                            index: animation_indices.first,
-                        },
-                    ),
-                    animation_indices,
-                    AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-                ))
-                .id();
-
-            commands.entity(enemy_entity).add_child(fire_sprite);
         }
     }
 }
@@ -0,0 +1,207 @@
+use crate::utils::YSort;
+use crate::DisplaySettings;
+use crate::GameState;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::Rng;
+
+const NUMBER_LIFETIME: f32 = 0.6;
+const NUMBER_RISE_SPEED: f32 = 30.0;
+/// How long damage over time is summed up before it is shown as one number.
+const DOT_INTERVAL: f32 = 0.5;
+
+pub struct DamageNumbersPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for DamageNumbersPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>();
+        app.insert_resource(DotTally::default());
+        app.add_systems(
+            Update,
+            (spawn_damage_numbers, flush_dot_tally, float_damage_numbers)
+                .chain()
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Playing)),
+        );
+        app.add_systems(OnExit(self.state.clone()), clean_up_damage_numbers);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DamageKind {
+    Normal,
+    Fire,
+    Freeze,
+    /// Damage amplified by freezer burn
+    Vulnerable,
+    Crit,
+    Shock,
+    Poison,
+}
+
+impl DamageKind {
+    fn color(self) -> Color {
+        match self {
+            DamageKind::Normal => Color::srgb(0.15, 0.15, 0.2),
+            DamageKind::Fire => Color::srgb(0.95, 0.45, 0.1),
+            DamageKind::Freeze => Color::srgb(0.2, 0.55, 0.95),
+            DamageKind::Vulnerable => Color::srgb(0.7, 0.2, 0.8),
+            DamageKind::Crit => Color::srgb(0.9, 0.1, 0.1),
+            DamageKind::Shock => Color::srgb(0.85, 0.75, 0.05),
+            DamageKind::Poison => Color::srgb(0.2, 0.7, 0.3),
+        }
+    }
+    fn font_size(self) -> f32 {
+        match self {
+            DamageKind::Crit => 12.0,
+            _ => 8.0,
+        }
+    }
+}
+
+/// Sent whenever an enemy takes damage, so it can be shown as a floating number.
+#[derive(Event)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub position: Vec2,
+    pub amount: f32,
+    pub kind: DamageKind,
+    /// Damage over time is summed per target instead of spawning a number every frame.
+    pub over_time: bool,
+}
+
+#[derive(Component)]
+struct DamageNumber {
+    timer: Timer,
+}
+
+struct PendingDot {
+    position: Vec2,
+    amount: f32,
+    elapsed: f32,
+}
+
+/// Damage over time per target and kind, so fire and poison on one enemy get separate numbers.
+#[derive(Resource, Default)]
+struct DotTally(HashMap<(Entity, DamageKind), PendingDot>);
+
+fn spawn_damage_number(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    position: Vec2,
+    amount: f32,
+    kind: DamageKind,
+) {
+    if amount < 0.5 {
+        return;
+    }
+    // Spread numbers out a little so simultaneous hits don't overlap exactly
+    let jitter = Vec2::new(
+        rand::thread_rng().gen_range(-6.0..6.0),
+        rand::thread_rng().gen_range(0.0..6.0),
+    );
+    commands.spawn((
+        Text2d::new(format!("{:.0}", amount)),
+        TextFont {
+            font: asset_server.load("fonts/slkscr.ttf"),
+            font_size: kind.font_size(),
+            ..default()
+        },
+        TextColor(kind.color()),
+        Transform::from_translation((position + Vec2::new(0.0, 12.0) + jitter).extend(0.0)),
+        YSort { z: 100.0 },
+        DamageNumber {
+            timer: Timer::from_seconds(NUMBER_LIFETIME, TimerMode::Once),
+        },
+    ));
+}
+
+fn spawn_damage_numbers(
+    mut commands: Commands,
+    mut damage_events: EventReader<DamageEvent>,
+    mut dot_tally: ResMut<DotTally>,
+    asset_server: Res<AssetServer>,
+    settings: Res<DisplaySettings>,
+) {
+    if !settings.damage_numbers {
+        damage_events.clear();
+        return;
+    }
+    for event in damage_events.read() {
+        if event.over_time {
+            let pending = dot_tally
+                .0
+                .entry((event.target, event.kind))
+                .or_insert(PendingDot {
+                    position: event.position,
+                    amount: 0.0,
+                    elapsed: 0.0,
+                });
+            pending.position = event.position;
+            pending.amount += event.amount;
+            continue;
+        }
+        spawn_damage_number(
+            &mut commands,
+            &asset_server,
+            event.position,
+            event.amount,
+            event.kind,
+        );
+    }
+}
+
+fn flush_dot_tally(
+    mut commands: Commands,
+    mut dot_tally: ResMut<DotTally>,
+    q_alive: Query<()>,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+) {
+    dot_tally.0.retain(|&(target, kind), pending| {
+        pending.elapsed += time.delta_secs();
+        // Dead targets show whatever they took since the last number
+        let alive = q_alive.get(target).is_ok();
+        if pending.elapsed < DOT_INTERVAL && alive {
+            return true;
+        }
+        spawn_damage_number(
+            &mut commands,
+            &asset_server,
+            pending.position,
+            pending.amount,
+            kind,
+        );
+        pending.elapsed = 0.0;
+        pending.amount = 0.0;
+        alive
+    });
+}
+
+fn float_damage_numbers(
+    mut commands: Commands,
+    mut q: Query<(Entity, &mut Transform, &mut TextColor, &mut DamageNumber)>,
+    time: Res<Time>,
+) {
+    for (entity, mut transform, mut color, mut number) in q.iter_mut() {
+        number.timer.tick(time.delta());
+        transform.translation.y += NUMBER_RISE_SPEED * time.delta_secs();
+        color.0.set_alpha(1.0 - number.timer.fraction());
+        if number.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn clean_up_damage_numbers(
+    mut commands: Commands,
+    q: Query<Entity, With<DamageNumber>>,
+    mut dot_tally: ResMut<DotTally>,
+) {
+    dot_tally.0.clear();
+    for entity in q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,174 @@
+use crate::collision::{DestroyAfter, InvincibleTimer};
+use crate::physics::KinematicBody;
+use crate::player::{Player, PlayerStats};
+use crate::GameState;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+
+pub const DASH_KEY: KeyCode = KeyCode::Space;
+/// Drag while dashing, which also decides how far the burst carries.
+pub const DASH_DRAG: f32 = 6.0;
+const DASH_IFRAME_DURATION: f32 = 0.3;
+/// Upper bound on a dash, in case the snowball never slows back down to its top speed.
+const MAX_DASH_DURATION: f32 = 0.5;
+const FREEZE_TRAIL_INTERVAL: f32 = 0.03;
+const FREEZE_TRAIL_LIFETIME: f32 = 1.5;
+
+pub struct DashPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for DashPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (start_dash, end_dash, recharge_dash, leave_freeze_trail)
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Playing)),
+        );
+        app.add_systems(OnExit(self.state.clone()), clean_up_freeze_trail);
+    }
+}
+
+#[derive(Component)]
+pub struct DashCharges {
+    pub charges: u32,
+    pub recharge: Timer,
+}
+
+impl DashCharges {
+    pub fn new(stats: &PlayerStats) -> Self {
+        Self {
+            charges: stats.dash_charges,
+            recharge: Timer::from_seconds(stats.dash_cooldown, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Dashing {
+    timer: Timer,
+    trail_timer: Timer,
+}
+
+/// Left behind by a dash with the freezing trail upgrade; freezes enemies that cross it.
+#[derive(Component)]
+pub struct FreezeTrail;
+
+#[allow(clippy::type_complexity)]
+fn start_dash(
+    mut commands: Commands,
+    mut q_player: Query<
+        (Entity, &mut KinematicBody, &mut DashCharges),
+        (With<Player>, Without<Dashing>),
+    >,
+    keys: Res<ButtonInput<KeyCode>>,
+    stats: Res<PlayerStats>,
+) {
+    let Ok((player_entity, mut body, mut dash)) = q_player.get_single_mut() else {
+        return;
+    };
+    if !keys.just_pressed(DASH_KEY) || dash.charges == 0 {
+        return;
+    }
+
+    let mut input = Vec2::ZERO;
+    if keys.pressed(KeyCode::KeyW) {
+        input.y += 1.0;
+    }
+    if keys.pressed(KeyCode::KeyS) {
+        input.y -= 1.0;
+    }
+    if keys.pressed(KeyCode::KeyA) {
+        input.x -= 1.0;
+    }
+    if keys.pressed(KeyCode::KeyD) {
+        input.x += 1.0;
+    }
+    let Some(direction) = input
+        .try_normalize()
+        .or_else(|| body.velocity.try_normalize())
+    else {
+        return;
+    };
+
+    dash.charges -= 1;
+    // Under DASH_DRAG the extra speed carries the snowball roughly dash_distance further
+    let mass = body.mass;
+    body.apply_impulse(direction * stats.dash_distance * DASH_DRAG * mass);
+
+    commands.entity(player_entity).insert((
+        Dashing {
+            timer: Timer::from_seconds(MAX_DASH_DURATION, TimerMode::Once),
+            trail_timer: Timer::from_seconds(FREEZE_TRAIL_INTERVAL, TimerMode::Repeating),
+        },
+        InvincibleTimer {
+            time_left: DASH_IFRAME_DURATION,
+        },
+    ));
+}
+
+fn end_dash(
+    mut commands: Commands,
+    mut q_player: Query<(Entity, &KinematicBody, &mut Dashing)>,
+    time: Res<Time>,
+) {
+    for (entity, body, mut dashing) in q_player.iter_mut() {
+        dashing.timer.tick(time.delta());
+        let back_to_top_speed = body
+            .max_speed
+            .is_some_and(|max_speed| body.velocity.length() <= max_speed);
+        if back_to_top_speed || dashing.timer.finished() {
+            commands.entity(entity).remove::<Dashing>();
+        }
+    }
+}
+
+fn recharge_dash(mut q_player: Query<&mut DashCharges>, time: Res<Time>, stats: Res<PlayerStats>) {
+    for mut dash in q_player.iter_mut() {
+        // Only write to a full set of charges once, so the HUD's change filter can skip it
+        if dash.charges >= stats.dash_charges {
+            if dash.recharge.elapsed_secs() > 0.0 {
+                dash.recharge.reset();
+            }
+            continue;
+        }
+        dash.recharge
+            .set_duration(std::time::Duration::from_secs_f32(stats.dash_cooldown));
+        if dash.recharge.tick(time.delta()).just_finished() {
+            dash.charges += 1;
+            dash.recharge.reset();
+        }
+    }
+}
+
+fn leave_freeze_trail(
+    mut commands: Commands,
+    mut q_player: Query<(&Transform, &mut Dashing)>,
+    asset_server: Res<AssetServer>,
+    stats: Res<PlayerStats>,
+    time: Res<Time>,
+) {
+    if !stats.dash_freeze_trail {
+        return;
+    }
+    for (transform, mut dashing) in q_player.iter_mut() {
+        if dashing.trail_timer.tick(time.delta()).just_finished() {
+            commands.spawn((
+                Sprite::from_image(asset_server.load("freeze.png")),
+                Transform::from_translation(transform.translation.truncate().extend(-50.0))
+                    .with_scale(Vec3::new(0.5, 0.5, 1.0)),
+                FreezeTrail,
+                DestroyAfter {
+                    duration: FREEZE_TRAIL_LIFETIME,
+                },
+            ));
+        }
+    }
+}
+
+fn clean_up_freeze_trail(mut commands: Commands, q_trail: Query<Entity, With<FreezeTrail>>) {
+    for entity in q_trail.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
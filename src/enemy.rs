@@ -1,17 +1,31 @@
-use crate::camera::InGameCamera;
-use crate::collision::Blink;
+use crate::audio::{Sfx, SfxEvent};
+use crate::camera::{InGameCamera, VIEW_RADIUS};
+use crate::character::RunStats;
+use crate::juice::Impact;
+use crate::physics::KinematicBody;
+use crate::pickup::DropPickup;
+use crate::pickup::DropSource;
+use crate::pickup::Magnetic;
+use crate::pickup::MAGNETIC_DRAG;
 use crate::player::Player;
 use crate::player::PlayerHealth;
+use crate::player::PlayerStats;
+use crate::snowball::SnowballMass;
+use crate::snowball::KILL_MASS;
+use crate::status::ApplyStatus;
+use crate::status::StatusEffects;
+use crate::status::StatusKind;
+use crate::status::StatusRegistry;
+use crate::status::StatusSource;
 
 use crate::GameState;
 
-use crate::collision::FlashingTimer;
 use crate::player::AnimationIndices;
 use crate::player::AnimationTimer;
-use crate::player::PlayerStats;
 use crate::utils::YSort;
 
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 use rand::Rng;
 pub struct EnemyPlugin<S: States> {
     pub state: S,
@@ -27,22 +41,27 @@ impl<S: States> Plugin for EnemyPlugin<S> {
             default_time: 1.,
             agressive_time: 0.1,
         });
+        app.insert_resource(XpMergeTimer(Timer::from_seconds(0.5, TimerMode::Repeating)));
         app.insert_resource(EnemyCount {
             enemy_count: 0,
             max_enemies: 100,
             min_enemies: 0,
         });
+        app.add_systems(
+            FixedUpdate,
+            chase_player
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Playing)),
+        );
         app.add_systems(
             Update,
             (
-                chase_player,
                 spawn_enemy,
+                promote_elites,
                 wiggle,
                 y_sort,
                 kill_dead_enemies,
-                unfreeze,
-                extinguish,
-                vunerable_tickdown,
+                merge_xp_orbs,
             )
                 .run_if(in_state(self.state.clone()))
                 .run_if(in_state(GameState::Playing)),
@@ -51,6 +70,19 @@ impl<S: States> Plugin for EnemyPlugin<S> {
     }
 }
 
+/// Drag on anything chasing the player; high so knockback settles quickly.
+const CHASER_DRAG: f32 = 8.0;
+const ELITE_CHANCE: f64 = 0.02;
+const ELITE_HEALTH_MULTIPLIER: f32 = 4.0;
+const ELITE_SCALE: f32 = 1.5;
+const ELITE_XP_MULTIPLIER: f32 = 5.0;
+/// Past this many orbs on the map, nearby ones merge into gems.
+const MAX_XP_ORBS: usize = 150;
+/// Orbs within the same cell of this size merge; the cell doubles until under the cap.
+const XP_MERGE_CELL: f32 = 48.0;
+/// Chill never slows enemies below this share of their speed; that's what freezing is for.
+const MIN_CHILL_SPEED: f32 = 0.2;
+
 #[derive(Resource)]
 pub struct EnemyTimer {
     spawn_time: f32,
@@ -69,15 +101,18 @@ pub struct EnemyCount {
     pub min_enemies: i32,
 }
 
-#[derive(Component)]
-pub struct Vunerable {
-    pub multiplier: f32,
-    pub duration: f32,
-}
 #[derive(Component)]
 pub struct EnemyHealth {
     pub health: f32,
+    pub max_health: f32,
 }
+/// XP an enemy drops when it dies.
+#[derive(Component)]
+pub struct XpReward(pub f32);
+
+#[derive(Resource)]
+struct XpMergeTimer(Timer);
+
 #[derive(Component)]
 pub struct EnemyXp {
     pub xp: f32,
@@ -87,17 +122,9 @@ pub struct ChasePlayer {
     pub speed: f32,
     pub radius: f32,
 }
-#[derive(Component)]
-pub struct Frozen {
-    pub duration: f32,
-}
-#[derive(Component)]
-pub struct OnFire {
-    pub duration: f32,
-}
 
 fn spawn_enemy(
-    q_camera: Query<(&Camera, &GlobalTransform), With<InGameCamera>>,
+    q_camera: Query<(&GlobalTransform, &OrthographicProjection), With<InGameCamera>>,
     time: Res<Time>,
     mut timer: ResMut<EnemyTimer>,
     mut commands: Commands,
@@ -109,17 +136,17 @@ fn spawn_enemy(
         timer.next_enemy_time -= time.delta_secs();
     }
 
-    if (timer.next_enemy_time < 0.) {
+    if timer.next_enemy_time < 0. {
         timer.next_enemy_reached = true;
         timer.next_enemy_time = 500.;
         enemy_count.min_enemies = 0;
     }
 
     timer.wave_time -= time.delta_secs();
-    if (timer.wave_time <= 0.) {
+    if timer.wave_time <= 0. {
         enemy_count.min_enemies += 1;
         enemy_count.max_enemies += 1;
-        if (timer.next_enemy_reached) {
+        if timer.next_enemy_reached {
             timer.wave_time = 2.;
         } else {
             timer.wave_time = 3.;
@@ -137,30 +164,13 @@ fn spawn_enemy(
 
         enemy_count.enemy_count += 1;
 
-        let circle = Circle::new(350.0);
+        // Just off screen however far the camera has zoomed out
+        let (camera_transform, projection) = q_camera.single();
+        let circle = Circle::new(VIEW_RADIUS * projection.scale);
         let boundary_pt = circle.sample_boundary(&mut rand::thread_rng());
-        let (_camera, camera_transform) = q_camera.single();
 
         let num_offset = rand::thread_rng().gen_range(-1.0..1.0);
-        if (!timer.next_enemy_reached) {
-            let snowman_holder = commands
-                .spawn((
-                    Visibility::Visible,
-                    Transform::from_xyz(
-                        boundary_pt.x + camera_transform.translation().x,
-                        boundary_pt.y + camera_transform.translation().y,
-                        2.0,
-                    ),
-                    YSort { z: 32.0 },
-                    Enemy,
-                    ChasePlayer {
-                        speed: 25.0,
-                        radius: 10000.0,
-                    },
-                    EnemyHealth { health: 100. },
-                ))
-                .id();
-
+        let snowman_holder = if !timer.next_enemy_reached {
             let snowman_sprite = commands
                 .spawn((
                     Sprite::from_image(asset_server.load("Snowman.png")),
@@ -188,15 +198,6 @@ fn spawn_enemy(
                     },
                 ))
                 .id();
-            commands.entity(snowman_holder).add_child(snowman_shadow);
-            commands.entity(snowman_holder).add_child(snowman_sprite);
-        } else {
-            let texture = asset_server.load("BuffSnowman.png");
-            let layout = TextureAtlasLayout::from_grid(UVec2::splat(48), 4, 1, None, None);
-            let texture_atlas_layout = texture_atlas_layouts.add(layout);
-            // Use only the subset of sprites in the sheet that make up the run animation
-            let animation_indices = AnimationIndices { first: 0, last: 3 };
-
             let snowman_holder = commands
                 .spawn((
                     Visibility::Visible,
@@ -208,13 +209,30 @@ fn spawn_enemy(
                     YSort { z: 32.0 },
                     Enemy,
                     ChasePlayer {
-                        speed: 26.0,
+                        speed: 25.0,
                         radius: 10000.0,
                     },
-                    EnemyHealth { health: 200. },
+                    EnemyHealth {
+                        health: 100.,
+                        max_health: 100.,
+                    },
+                    KinematicBody::new(1.0, CHASER_DRAG),
+                    XpReward(1.0),
+                    StatusEffects::default(),
+                    EnemyVisual(snowman_sprite),
                 ))
                 .id();
 
+            commands.entity(snowman_holder).add_child(snowman_shadow);
+            commands.entity(snowman_holder).add_child(snowman_sprite);
+            snowman_holder
+        } else {
+            let texture = asset_server.load("BuffSnowman.png");
+            let layout = TextureAtlasLayout::from_grid(UVec2::splat(48), 4, 1, None, None);
+            let texture_atlas_layout = texture_atlas_layouts.add(layout);
+            // Use only the subset of sprites in the sheet that make up the run animation
+            let animation_indices = AnimationIndices { first: 0, last: 3 };
+
             let snowman_sprite = commands
                 .spawn((
                     Sprite::from_atlas_image(
@@ -250,12 +268,62 @@ fn spawn_enemy(
                     },
                 ))
                 .id();
+            let snowman_holder = commands
+                .spawn((
+                    Visibility::Visible,
+                    Transform::from_xyz(
+                        boundary_pt.x + camera_transform.translation().x,
+                        boundary_pt.y + camera_transform.translation().y,
+                        2.0,
+                    ),
+                    YSort { z: 32.0 },
+                    Enemy,
+                    ChasePlayer {
+                        speed: 26.0,
+                        radius: 10000.0,
+                    },
+                    EnemyHealth {
+                        health: 200.,
+                        max_health: 200.,
+                    },
+                    KinematicBody::new(2.0, CHASER_DRAG),
+                    XpReward(3.0),
+                    StatusEffects::default(),
+                    EnemyVisual(snowman_sprite),
+                ))
+                .id();
+
             commands.entity(snowman_holder).add_child(snowman_shadow);
             commands.entity(snowman_holder).add_child(snowman_sprite);
+            snowman_holder
+        };
+
+        if rand::thread_rng().gen_bool(ELITE_CHANCE) {
+            commands.entity(snowman_holder).insert(Elite);
         }
     }
 }
 
+/// Elites are bigger and tougher, and always drop something when they die.
+#[derive(Component)]
+pub struct Elite;
+
+fn promote_elites(
+    mut q: Query<(&mut EnemyHealth, &mut XpReward, &mut Transform), Added<Elite>>,
+    mut sfx: EventWriter<SfxEvent>,
+) {
+    for (mut health, mut xp_reward, mut transform) in q.iter_mut() {
+        sfx.send(SfxEvent::at(
+            Sfx::EliteSpawn,
+            transform.translation.truncate(),
+        ));
+        health.max_health *= ELITE_HEALTH_MULTIPLIER;
+        health.health = health.max_health;
+        xp_reward.0 *= ELITE_XP_MULTIPLIER;
+        transform.scale = Vec3::splat(ELITE_SCALE);
+    }
+}
+
 #[derive(Component)]
 pub struct Wiggle {
     pub rotate_speed: f32,
@@ -265,8 +333,19 @@ pub struct Wiggle {
     pub offset: f32,
 }
 
-fn wiggle(time: Res<Time>, mut q: Query<(&mut Transform, &Wiggle), Without<Frozen>>) {
-    for (mut tf, wiggle) in q.iter_mut() {
+fn wiggle(
+    time: Res<Time>,
+    mut q: Query<(&mut Transform, &Wiggle, &Parent)>,
+    q_statuses: Query<&StatusEffects>,
+) {
+    for (mut tf, wiggle, parent) in q.iter_mut() {
+        // Frozen enemies hold still
+        if q_statuses
+            .get(parent.get())
+            .is_ok_and(|statuses| statuses.has(StatusKind::Frozen))
+        {
+            continue;
+        }
         let rotate_sin = f32::sin(wiggle.offset + time.elapsed_secs() * wiggle.rotate_speed);
         let scale_sin = f32::sin(wiggle.offset + time.elapsed_secs() * wiggle.scale_speed);
         tf.rotate_z(rotate_sin * wiggle.rotate_amount);
@@ -280,100 +359,189 @@ fn wiggle(time: Res<Time>, mut q: Query<(&mut Transform, &Wiggle), Without<Froze
 
 #[derive(Component)]
 pub struct Enemy;
+/// The enemy's main sprite, which flashes, blinks and gets tinted; separate from shadows and overlays.
+#[derive(Component)]
+pub struct EnemyVisual(pub Entity);
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn kill_dead_enemies(
     mut commands: Commands,
-    enemy_q: Query<(&EnemyHealth, &Transform, Entity), (With<Enemy>, Without<EnemyXp>)>,
-    mut q_player: Query<&mut PlayerHealth, With<Player>>,
+    enemy_q: Query<
+        (
+            &EnemyHealth,
+            &Transform,
+            Entity,
+            &StatusEffects,
+            &XpReward,
+            Option<&Elite>,
+        ),
+        (With<Enemy>, Without<EnemyXp>),
+    >,
+    mut q_player: Query<(&mut PlayerHealth, &mut SnowballMass), With<Player>>,
     asset_server: Res<AssetServer>,
+    mut sfx: EventWriter<SfxEvent>,
     mut enemy_count: ResMut<EnemyCount>,
+    mut run_stats: ResMut<RunStats>,
+    mut apply_status: EventWriter<ApplyStatus>,
+    registry: Res<StatusRegistry>,
+    stats: Res<PlayerStats>,
+    mut drops: EventWriter<DropPickup>,
+    mut impacts: EventWriter<Impact>,
 ) {
-    let Ok(mut player_health) = q_player.get_single_mut() else {
+    let Ok((mut player_health, mut snowball)) = q_player.get_single_mut() else {
         return;
     };
-    for (health, transform, entity) in enemy_q.iter() {
+    for (health, transform, entity, statuses, xp_reward, elite) in enemy_q.iter() {
         if health.health <= 0. {
-            commands.entity(entity).insert(AudioPlayer::new(
-                asset_server.load("sounds/snowman_death.ogg"),
+            // Contagious effects jump to whoever is standing close by
+            for effect in &statuses.effects {
+                let Some(spread_radius) = registry.def(effect.kind).spreads_on_death else {
+                    continue;
+                };
+                let radius = spread_radius(&stats);
+                for (other_health, other_tf, other_entity, _, _, _) in enemy_q.iter() {
+                    if other_health.health > 0.
+                        && other_tf.translation.distance(transform.translation) < radius
+                    {
+                        apply_status.send(ApplyStatus {
+                            target: other_entity,
+                            kind: effect.kind,
+                            source: StatusSource::Reaction,
+                        });
+                    }
+                }
+            }
+            sfx.send(SfxEvent::at(
+                Sfx::EnemyDeath,
+                transform.translation.truncate(),
             ));
             commands.entity(entity).despawn_recursive();
             enemy_count.enemy_count -= 1;
+            run_stats.kills += 1;
+            let (color, scale) = xp_gem_look(xp_reward.0);
             commands.spawn((
-                EnemyXp { xp: 1.0 },
-                Sprite::from_image(asset_server.load("xp.png")),
-                Transform::from_xyz(transform.translation.x, transform.translation.y, 0.0),
-                ChasePlayer {
-                    speed: 200.0,
-                    radius: 80.0,
+                EnemyXp { xp: xp_reward.0 },
+                Sprite {
+                    image: asset_server.load("xp.png"),
+                    color,
+                    ..default()
                 },
+                Transform::from_xyz(transform.translation.x, transform.translation.y, 0.0)
+                    .with_scale(Vec3::splat(scale)),
+                Magnetic::default(),
+                KinematicBody::new(1.0, MAGNETIC_DRAG),
             ));
+            if elite.is_some() {
+                impacts.send(Impact::ELITE_DEATH);
+            }
+            drops.send(DropPickup {
+                position: transform.translation.truncate(),
+                source: if elite.is_some() {
+                    DropSource::Elite
+                } else {
+                    DropSource::EnemyDeath
+                },
+            });
             player_health.hp += 0.2;
+            snowball.add(KILL_MASS);
         }
     }
 }
 
-fn chase_player(
+/// Gems get bigger and change colour with the XP they're worth.
+fn xp_gem_look(xp: f32) -> (Color, f32) {
+    if xp >= 100.0 {
+        (Color::srgb(1.0, 0.8, 0.2), 2.0)
+    } else if xp >= 25.0 {
+        (Color::srgb(0.75, 0.35, 1.0), 1.6)
+    } else if xp >= 5.0 {
+        (Color::srgb(0.35, 0.6, 1.0), 1.3)
+    } else {
+        (Color::WHITE, 1.0)
+    }
+}
+
+/// Keeps the number of orbs bounded by merging ones that share a cell into a single gem.
+fn merge_xp_orbs(
+    mut commands: Commands,
+    mut q_xp: Query<(Entity, &mut Transform, &mut EnemyXp, &mut Sprite)>,
+    mut merge_timer: ResMut<XpMergeTimer>,
     time: Res<Time>,
-    q_player: Query<(&GlobalTransform, &Player)>,
-    mut q: Query<(&mut Transform, &ChasePlayer), Without<Frozen>>,
 ) {
-    let Ok((player, _player_transform)) = q_player.get_single() else {
+    if !merge_timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let mut orbs: Vec<(Entity, Vec2, f32)> = q_xp
+        .iter()
+        .map(|(entity, transform, xp, _)| (entity, transform.translation.truncate(), xp.xp))
+        .collect();
+    if orbs.len() <= MAX_XP_ORBS {
         return;
-    };
-    //println!("PlayerPositon coords: {}/{}", player.translation().x, player.translation().y)
-    for (mut tf, chase_player) in q.iter_mut() {
-        if tf.translation.distance(player.translation()) > chase_player.radius {
-            continue;
-        }
-        let dt = time.delta_secs() * chase_player.speed as f32;
-        let dir = (player.translation().truncate() - tf.translation.truncate())
-            .normalize()
-            .extend(0.0);
-        tf.translation += dir * dt;
     }
-}
 
-fn unfreeze(mut commands: Commands, mut q: Query<(&mut Frozen, Entity)>, time: Res<Time>) {
-    for (mut frozen, frozen_entity) in q.iter_mut() {
-        let dt = time.delta_secs();
-        frozen.duration -= dt;
-        if frozen.duration <= 0.0 {
-            commands.entity(frozen_entity).remove::<Frozen>();
+    let mut cell = XP_MERGE_CELL;
+    while orbs.len() > MAX_XP_ORBS {
+        let mut cells: HashMap<IVec2, usize> = HashMap::new();
+        let mut merged: Vec<(Entity, Vec2, f32)> = Vec::with_capacity(orbs.len());
+        for (entity, position, xp) in orbs {
+            let key = (position / cell).floor().as_ivec2();
+            match cells.get(&key) {
+                Some(&index) => {
+                    merged[index].2 += xp;
+                    commands.entity(entity).despawn_recursive();
+                }
+                None => {
+                    cells.insert(key, merged.len());
+                    merged.push((entity, position, xp));
+                }
+            }
         }
+        orbs = merged;
+        cell *= 2.0;
     }
-}
 
-fn extinguish(
-    mut commands: Commands,
-    mut q: Query<(&mut OnFire, &mut EnemyHealth, &Children, Entity)>,
-    time: Res<Time>,
-    stats: Res<PlayerStats>,
-) {
-    for (mut on_fire, mut enemy_health, children, on_fire_entity) in q.iter_mut() {
-        let dt = time.delta_secs();
-        on_fire.duration -= dt;
-        enemy_health.health -= stats.fire_dps * dt;
-        if on_fire.duration <= 0.0 {
-            commands.entity(on_fire_entity).remove::<OnFire>();
-            commands.entity(children[1]).remove::<Blink>();
-            commands.entity(children[1]).insert(FlashingTimer {
-                time_left: 0.0,
-                color: Color::srgba(1., 1., 1., 1.),
-            });
+    for (entity, _, total) in orbs {
+        let Ok((_, mut transform, mut xp, mut sprite)) = q_xp.get_mut(entity) else {
+            continue;
+        };
+        if xp.xp == total {
+            continue;
         }
+        xp.xp = total;
+        let (color, scale) = xp_gem_look(total);
+        sprite.color = color;
+        transform.scale = Vec3::splat(scale);
     }
 }
 
-fn vunerable_tickdown(
-    mut commands: Commands,
-    mut q: Query<(&mut Vunerable, Entity)>,
-    time: Res<Time>,
+fn chase_player(
+    q_player: Query<&GlobalTransform, With<Player>>,
+    mut q: Query<(
+        &Transform,
+        &mut KinematicBody,
+        &ChasePlayer,
+        Option<&StatusEffects>,
+    )>,
 ) {
-    for (mut vunerable, vunerable_entity) in q.iter_mut() {
-        let dt = time.delta_secs();
-        vunerable.duration -= dt;
-        if vunerable.duration <= 0.0 {
-            commands.entity(vunerable_entity).remove::<Vunerable>();
+    let Ok(player) = q_player.get_single() else {
+        return;
+    };
+    for (tf, mut body, chase_player, statuses) in q.iter_mut() {
+        let mut speed = chase_player.speed;
+        if let Some(statuses) = statuses {
+            if statuses.has(StatusKind::Frozen) {
+                continue;
+            }
+            if let Some(chilled) = statuses.get(StatusKind::Chilled) {
+                speed *= (1.0 - chilled.potency * chilled.stacks as f32).max(MIN_CHILL_SPEED);
+            }
+        }
+        if tf.translation.distance(player.translation()) > chase_player.radius {
+            continue;
         }
+        let dir = (player.translation().truncate() - tf.translation.truncate()).normalize_or_zero();
+        // Against CHASER_DRAG this settles at exactly `speed`
+        let force = dir * speed * body.drag * body.mass;
+        body.add_force(force);
     }
 }
 
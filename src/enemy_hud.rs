@@ -0,0 +1,208 @@
+use crate::enemy::Enemy;
+use crate::enemy::EnemyHealth;
+use crate::status::StatusEffects;
+use crate::status::StatusKind;
+use crate::status::StatusRegistry;
+use crate::DisplaySettings;
+use crate::GameState;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+
+const HEALTH_BAR_WIDTH: f32 = 16.0;
+const HEALTH_BAR_OFFSET: f32 = -14.0;
+const STATUS_ICON_SIZE: f32 = 6.0;
+const STATUS_ICON_OFFSET: f32 = 14.0;
+
+pub struct EnemyHudPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for EnemyHudPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (attach_enemy_hud, update_health_bars, update_status_icons)
+                .chain()
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// Direct links to an enemy's bar and icon entities, so updates don't walk the hierarchy.
+#[derive(Component)]
+struct EnemyHud {
+    health_bar: Entity,
+    health_fill: Entity,
+    /// Icon and its duration bar for each effect currently on the enemy
+    status_icons: Vec<(StatusKind, Entity, Entity)>,
+}
+
+/// A flat bar growing to the right from its left edge, so only the width needs updating.
+fn spawn_bar(parent: &mut ChildBuilder, width: f32, height: f32, y: f32, color: Color) -> Entity {
+    parent
+        .spawn((
+            Sprite {
+                color,
+                custom_size: Some(Vec2::new(width, height)),
+                anchor: Anchor::CenterLeft,
+                ..default()
+            },
+            Transform::from_xyz(-width / 2.0, y, 0.1),
+        ))
+        .id()
+}
+
+fn set_visibility(visibility: &mut Mut<Visibility>, visible: bool) {
+    let new_visibility = if visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    if **visibility != new_visibility {
+        **visibility = new_visibility;
+    }
+}
+
+fn set_width(sprite: &mut Mut<Sprite>, full_width: f32, fraction: f32) {
+    // Snap to whole pixels so bars don't change every frame for sub-pixel differences
+    let width = (full_width * fraction.clamp(0.0, 1.0)).ceil();
+    if let Some(size) = sprite.custom_size {
+        if size.x != width {
+            sprite.custom_size = Some(Vec2::new(width, size.y));
+        }
+    }
+}
+
+fn attach_enemy_hud(mut commands: Commands, q_enemy: Query<Entity, Added<Enemy>>) {
+    for enemy_entity in q_enemy.iter() {
+        let mut health_bar = Entity::PLACEHOLDER;
+        let mut health_fill = Entity::PLACEHOLDER;
+        commands.entity(enemy_entity).with_children(|parent| {
+            health_bar = parent
+                .spawn((
+                    Transform::from_xyz(0.0, HEALTH_BAR_OFFSET, 1.0),
+                    Visibility::Hidden,
+                ))
+                .with_children(|parent| {
+                    spawn_bar(
+                        parent,
+                        HEALTH_BAR_WIDTH,
+                        2.0,
+                        0.0,
+                        Color::srgb(0.2, 0.2, 0.2),
+                    );
+                    health_fill = spawn_bar(
+                        parent,
+                        HEALTH_BAR_WIDTH,
+                        2.0,
+                        0.0,
+                        Color::srgb(0.85, 0.2, 0.2),
+                    );
+                })
+                .id();
+        });
+        commands.entity(enemy_entity).insert(EnemyHud {
+            health_bar,
+            health_fill,
+            status_icons: Vec::new(),
+        });
+    }
+}
+
+fn update_health_bars(
+    q_enemy: Query<(Ref<EnemyHealth>, &EnemyHud)>,
+    mut q_visibility: Query<&mut Visibility>,
+    mut q_sprite: Query<&mut Sprite>,
+    settings: Res<DisplaySettings>,
+) {
+    for (health, hud) in q_enemy.iter() {
+        if !health.is_changed() && !settings.is_changed() {
+            continue;
+        }
+        let fraction = health.health / health.max_health;
+        if let Ok(mut visibility) = q_visibility.get_mut(hud.health_bar) {
+            set_visibility(
+                &mut visibility,
+                settings.enemy_health_bars && fraction < 1.0,
+            );
+        }
+        if let Ok(mut sprite) = q_sprite.get_mut(hud.health_fill) {
+            set_width(&mut sprite, HEALTH_BAR_WIDTH, fraction);
+        }
+    }
+}
+
+/// Spawns an icon when an effect lands and despawns it when the effect wears off, keeping
+/// the row centred over the enemy.
+fn update_status_icons(
+    mut commands: Commands,
+    mut q_enemy: Query<(Entity, &mut EnemyHud, &StatusEffects), Changed<StatusEffects>>,
+    mut q_sprite: Query<&mut Sprite>,
+    asset_server: Res<AssetServer>,
+    registry: Res<StatusRegistry>,
+) {
+    for (enemy_entity, mut hud, statuses) in q_enemy.iter_mut() {
+        let icon_count = hud.status_icons.len();
+        hud.status_icons.retain(|(kind, icon, _)| {
+            let active = statuses.has(*kind);
+            if !active {
+                commands.entity(*icon).despawn_recursive();
+            }
+            active
+        });
+        let mut layout_changed = hud.status_icons.len() != icon_count;
+
+        for effect in &statuses.effects {
+            if let Some((_, _, timer_bar)) = hud
+                .status_icons
+                .iter()
+                .find(|(kind, _, _)| *kind == effect.kind)
+            {
+                if let Ok(mut sprite) = q_sprite.get_mut(*timer_bar) {
+                    set_width(
+                        &mut sprite,
+                        STATUS_ICON_SIZE,
+                        effect.duration / effect.max_duration,
+                    );
+                }
+                continue;
+            }
+            let def = registry.def(effect.kind);
+            let mut timer_bar = Entity::PLACEHOLDER;
+            let icon = commands
+                .spawn((
+                    Sprite {
+                        image: asset_server.load(def.icon),
+                        color: def.icon_color,
+                        custom_size: Some(Vec2::splat(STATUS_ICON_SIZE)),
+                        ..default()
+                    },
+                    Transform::from_xyz(0.0, STATUS_ICON_OFFSET, 1.0),
+                ))
+                .with_children(|parent| {
+                    timer_bar = spawn_bar(
+                        parent,
+                        STATUS_ICON_SIZE,
+                        1.0,
+                        -STATUS_ICON_SIZE / 2.0 - 1.0,
+                        Color::BLACK,
+                    );
+                })
+                .id();
+            commands.entity(enemy_entity).add_child(icon);
+            hud.status_icons.push((effect.kind, icon, timer_bar));
+            layout_changed = true;
+        }
+
+        if layout_changed {
+            let first_slot = (hud.status_icons.len() as f32 - 1.0) / 2.0;
+            for (slot, (_, icon, _)) in hud.status_icons.iter().enumerate() {
+                let x = (slot as f32 - first_slot) * (STATUS_ICON_SIZE + 2.0);
+                commands
+                    .entity(*icon)
+                    .insert(Transform::from_xyz(x, STATUS_ICON_OFFSET, 1.0));
+            }
+        }
+    }
+}
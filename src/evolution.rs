@@ -0,0 +1,71 @@
+use crate::player::PlayerStats;
+use crate::upgrade_ids;
+
+/// Evolved cards get upgrade ids from here up, in the order of `EVOLUTIONS`.
+pub const EVOLUTION_ID_BASE: u32 = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Evolution {
+    /// Chestnut shields roast into a flaming ring that sets everything it hits alight
+    RoastedChestnutRing,
+    /// Shurikens ice over and chill everything they hit
+    FrostbiteShurikens,
+}
+
+pub enum Requirement {
+    /// The upgrade has reached its max level
+    Maxed(u32),
+    /// The upgrade has been taken at least once
+    Taken(u32),
+}
+
+pub struct EvolutionDef {
+    pub evolution: Evolution,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Used for the card icon and the evolved weapon
+    pub sprite: &'static str,
+    pub requires: &'static [Requirement],
+}
+
+pub const EVOLUTIONS: [EvolutionDef; 2] = [
+    EvolutionDef {
+        evolution: Evolution::RoastedChestnutRing,
+        name: "Roasted Chestnut Ring",
+        description: "Your chestnuts roast into a flaming ring that burns everything it touches",
+        sprite: "roasted_chestnut.png",
+        requires: &[
+            Requirement::Maxed(upgrade_ids::CHESTNUT_SHIELD),
+            Requirement::Maxed(upgrade_ids::SHIELD_ROTATION_SPEED),
+            Requirement::Taken(upgrade_ids::FIRE_CHANCE),
+        ],
+    },
+    EvolutionDef {
+        evolution: Evolution::FrostbiteShurikens,
+        name: "Frostbite Shurikens",
+        description: "Your shurikens ice over, chilling every enemy they pierce",
+        sprite: "frostbite_shuriken.png",
+        requires: &[
+            Requirement::Maxed(upgrade_ids::RATE_OF_FIRE),
+            Requirement::Maxed(upgrade_ids::PIERCE),
+            Requirement::Taken(upgrade_ids::FREEZE_CHANCE),
+        ],
+    },
+];
+
+impl Evolution {
+    pub fn def(self) -> &'static EvolutionDef {
+        EVOLUTIONS
+            .iter()
+            .find(|def| def.evolution == self)
+            .expect("every evolution has a definition")
+    }
+
+    pub fn apply(self, stats: &mut PlayerStats) {
+        stats.evolutions.push(self);
+        match self {
+            Evolution::RoastedChestnutRing => stats.shield_damage *= 1.5,
+            Evolution::FrostbiteShurikens => stats.projectile_piercing += 2,
+        }
+    }
+}
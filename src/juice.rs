@@ -0,0 +1,123 @@
+use crate::{DisplaySettings, GameState};
+use bevy::prelude::*;
+
+/// Camera offset in pixels at full trauma
+const MAX_SHAKE_OFFSET: f32 = 8.0;
+/// Trauma lost per second
+const TRAUMA_DECAY: f32 = 1.5;
+/// How fast the shake wobbles
+const SHAKE_FREQUENCY: f32 = 30.0;
+/// Game speed while a hit-stop holds
+const HIT_STOP_SPEED: f32 = 0.05;
+
+pub struct JuicePlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for JuicePlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Impact>();
+        app.init_resource::<ScreenShake>();
+        app.init_resource::<HitStop>();
+        app.add_systems(
+            Update,
+            (apply_impacts, update_shake, update_hit_stop)
+                .chain()
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Playing)),
+        );
+        app.add_systems(OnExit(self.state.clone()), reset_juice);
+        app.add_systems(OnExit(GameState::Playing), reset_juice);
+    }
+}
+
+/// A big moment: shakes the screen by `trauma` (0 to 1) and freezes the game for `hit_stop` seconds.
+#[derive(Event, Clone, Copy)]
+pub struct Impact {
+    pub trauma: f32,
+    pub hit_stop: f32,
+}
+
+impl Impact {
+    pub const PLAYER_HIT: Impact = Impact {
+        trauma: 0.4,
+        hit_stop: 0.06,
+    };
+    pub const ELITE_DEATH: Impact = Impact {
+        trauma: 0.5,
+        hit_stop: 0.1,
+    };
+    pub const BOMB: Impact = Impact {
+        trauma: 0.8,
+        hit_stop: 0.12,
+    };
+    pub const FLASH_FREEZE: Impact = Impact {
+        trauma: 0.15,
+        hit_stop: 0.0,
+    };
+}
+
+/// `offset` is added on top of the camera follow.
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    trauma: f32,
+    elapsed: f32,
+    pub offset: Vec2,
+}
+
+#[derive(Resource, Default)]
+struct HitStop {
+    remaining: f32,
+}
+
+fn apply_impacts(
+    mut impacts: EventReader<Impact>,
+    mut shake: ResMut<ScreenShake>,
+    mut hit_stop: ResMut<HitStop>,
+    settings: Res<DisplaySettings>,
+) {
+    for impact in impacts.read() {
+        shake.trauma = (shake.trauma + impact.trauma * settings.screen_shake).min(1.0);
+        hit_stop.remaining = hit_stop.remaining.max(impact.hit_stop * settings.hit_stop);
+    }
+}
+
+/// Trauma is squared so small knocks barely register and big ones really rattle.
+fn update_shake(mut shake: ResMut<ScreenShake>, time: Res<Time<Real>>) {
+    shake.trauma = (shake.trauma - TRAUMA_DECAY * time.delta_secs()).max(0.0);
+    shake.elapsed += time.delta_secs();
+    let t = shake.elapsed * SHAKE_FREQUENCY;
+    // Two detuned sines per axis wander enough to read as noise
+    let wobble = Vec2::new(
+        t.sin() + (t * 1.7 + 1.3).sin() * 0.5,
+        (t * 1.1 + 4.2).sin() + (t * 2.3).sin() * 0.5,
+    ) / 1.5;
+    shake.offset = wobble * shake.trauma * shake.trauma * MAX_SHAKE_OFFSET;
+}
+
+/// Hit-stop runs on real time since it is the thing slowing virtual time down.
+fn update_hit_stop(
+    mut hit_stop: ResMut<HitStop>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    time: Res<Time<Real>>,
+) {
+    if hit_stop.remaining <= 0.0 {
+        return;
+    }
+    hit_stop.remaining -= time.delta_secs();
+    virtual_time.set_relative_speed(if hit_stop.remaining > 0.0 {
+        HIT_STOP_SPEED
+    } else {
+        1.0
+    });
+}
+
+fn reset_juice(
+    mut shake: ResMut<ScreenShake>,
+    mut hit_stop: ResMut<HitStop>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    *shake = ScreenShake::default();
+    *hit_stop = HitStop::default();
+    virtual_time.set_relative_speed(1.0);
+}
@@ -1,24 +1,44 @@
 mod audio;
 mod background;
 mod camera;
+mod character;
+mod chest;
 mod collision;
+mod damage_numbers;
+mod dash;
 mod enemy;
+mod enemy_hud;
+mod evolution;
+mod juice;
 mod mainmenu;
+mod physics;
 mod pickup;
 mod player;
+mod snowball;
+mod status;
 mod ui;
+mod upgrade_ids;
 mod utils;
 
 use audio::AudioPlugin;
 use background::BackgroundPlugin;
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
-use camera::CameraPlugin;
+use camera::{CameraPlugin, CameraZoomPlugin, ZoomBounds};
+use character::CharacterPlugin;
+use chest::ChestPlugin;
 use collision::CollisionPlugin;
+use damage_numbers::DamageNumbersPlugin;
+use dash::DashPlugin;
 use enemy::EnemyPlugin;
+use enemy_hud::EnemyHudPlugin;
+use juice::JuicePlugin;
 use mainmenu::MainMenuPlugin;
+use physics::PhysicsPlugin;
 use pickup::PickupPlugin;
 use player::PlayerPlugin;
+use snowball::SnowballPlugin;
+use status::StatusPlugin;
 use ui::UiPlugin;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, States)]
@@ -26,10 +46,13 @@ pub enum GameState {
     Playing,
     Paused,
     Upgrade,
+    /// Opening a chest picked up from an elite
+    Chest,
 }
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, States)]
 pub enum AppState {
     MainMenu,
+    CharacterSelect,
     InGame,
     GameOver,
     Settings,
@@ -41,6 +64,17 @@ pub enum AppState {
 pub struct Volume {
     pub music: f32,
     pub sfx: f32,
+    pub ui: f32,
+}
+
+#[derive(Resource)]
+pub struct DisplaySettings {
+    pub damage_numbers: bool,
+    pub enemy_health_bars: bool,
+    /// Scales screen shake; 0 turns it off
+    pub screen_shake: f32,
+    /// Scales how long hit-stops freeze the game; 0 turns them off
+    pub hit_stop: f32,
 }
 
 fn main() {
@@ -48,27 +82,63 @@ fn main() {
         .insert_resource(Volume {
             music: 1.0,
             sfx: 1.0,
+            ui: 1.0,
+        })
+        .insert_resource(DisplaySettings {
+            damage_numbers: true,
+            enemy_health_bars: true,
+            screen_shake: 1.0,
+            hit_stop: 1.0,
         })
         .add_plugins(CameraPlugin)
         .add_plugins(BackgroundPlugin)
         .insert_state(AppState::MainMenu)
         .insert_state(GameState::Playing)
         .add_plugins(AudioPlugin)
+        .add_plugins(CharacterPlugin)
         .add_plugins(MainMenuPlugin {
             state: AppState::MainMenu,
         })
         .add_plugins(EnemyPlugin {
             state: AppState::InGame,
         })
+        .add_plugins(EnemyHudPlugin {
+            state: AppState::InGame,
+        })
         .add_plugins(PlayerPlugin {
             state: AppState::InGame,
         })
+        .add_plugins(CameraZoomPlugin {
+            state: AppState::InGame,
+            bounds: ZoomBounds::SURVIVAL,
+        })
+        .add_plugins(DashPlugin {
+            state: AppState::InGame,
+        })
+        .add_plugins(PhysicsPlugin {
+            state: AppState::InGame,
+        })
+        .add_plugins(SnowballPlugin {
+            state: AppState::InGame,
+        })
+        .add_plugins(StatusPlugin {
+            state: AppState::InGame,
+        })
         .add_plugins(CollisionPlugin {
             state: AppState::InGame,
         })
+        .add_plugins(DamageNumbersPlugin {
+            state: AppState::InGame,
+        })
         .add_plugins(PickupPlugin {
             state: AppState::InGame,
         })
+        .add_plugins(ChestPlugin {
+            state: AppState::InGame,
+        })
+        .add_plugins(JuicePlugin {
+            state: AppState::InGame,
+        })
         .add_plugins(UiPlugin {
             state: AppState::InGame,
         })
@@ -1,11 +1,24 @@
-use crate::player::PlayerStats;
+use crate::audio::{Sfx, SfxEvent};
+use crate::character::{Progress, RunStats, SelectedCharacter, CHARACTERS};
+use crate::chest::RevealBurst;
+use crate::evolution::{Requirement, EVOLUTIONS, EVOLUTION_ID_BASE};
+use crate::player::{Player, PlayerHealth, PlayerStats, PlayerXp, PLAYER_START_HP};
+use crate::upgrade_ids;
 use crate::AppState;
+use crate::DisplaySettings;
 use crate::GameState;
 use crate::Volume;
 use bevy::input::keyboard::KeyCode;
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
 use rand::seq::SliceRandom;
+use rand::Rng;
+const SKIP_HEAL: f32 = 2.0;
+/// Share of the current level's XP a skip gives when already at full health.
+const SKIP_XP_FRACTION: f32 = 0.25;
+/// Time between shots Rate of Fire approaches but never goes below.
+const MIN_RATE_OF_FIRE: f32 = 0.1;
+
 struct ImageIcons {
     projectile_icon: Handle<Image>,
     shield_icon: Handle<Image>,
@@ -13,6 +26,7 @@ struct ImageIcons {
     freeze_icon: Handle<Image>,
     fire_icon: Handle<Image>,
     snowball_icon: Handle<Image>,
+    xp_icon: Handle<Image>,
 }
 impl FromWorld for ImageIcons {
     fn from_world(world: &mut World) -> Self {
@@ -20,10 +34,11 @@ impl FromWorld for ImageIcons {
         Self {
             projectile_icon: asset_server.load("candycane_shuriken.png"),
             shield_icon: asset_server.load("chestnut.png"),
-            pierce_icon: asset_server.load("candycane.png"),
+            pierce_icon: asset_server.load("Candycane.png"),
             freeze_icon: asset_server.load("freeze.png"),
             fire_icon: asset_server.load("fire_icon.png"),
             snowball_icon: asset_server.load("snowball_icon.png"),
+            xp_icon: asset_server.load("xp.png"),
         }
     }
 }
@@ -49,10 +64,16 @@ impl<S: States> Plugin for MainMenuPlugin<S> {
         app.insert_resource(Volume {
             music: 1.0,
             sfx: 1.0,
+            ui: 1.0,
         });
         app.add_systems(Startup, (load_fonts, load_upgrades));
 
         app.add_systems(Update, setup_main_menu.run_if(in_state(self.state.clone())));
+        app.add_systems(
+            Update,
+            setup_character_select.run_if(in_state(AppState::CharacterSelect)),
+        );
+        app.add_systems(OnEnter(AppState::InGame), reset_upgrades);
         app.add_systems(Update, setup_game_over.run_if(in_state(AppState::GameOver)));
         app.add_systems(
             Update,
@@ -68,6 +89,8 @@ impl<S: States> Plugin for MainMenuPlugin<S> {
                 .run_if(in_state(GameState::Upgrade))
                 .after(generate_available_upgrades),
         );
+        app.add_systems(OnEnter(GameState::Chest), open_chest);
+        app.add_systems(Update, chest_screen.run_if(in_state(GameState::Chest)));
         app.add_systems(Update, setup_settings.run_if(in_state(AppState::Settings)));
         app.add_systems(Update, credits_screen.run_if(in_state(AppState::Credits)));
         app.add_systems(Update, setup_tutorial.run_if(in_state(AppState::Tutorial)));
@@ -78,6 +101,7 @@ fn load_upgrades(
     mut context: EguiContexts,
     icons: Local<ImageIcons>,
     player_stats: Res<PlayerStats>,
+    asset_server: Res<AssetServer>,
 ) {
     let rendered_projectile_icon = context.add_image(icons.projectile_icon.clone());
     let rendered_shield_icon = context.add_image(icons.shield_icon.clone());
@@ -85,254 +109,456 @@ fn load_upgrades(
     let rendered_freeze_icon = context.add_image(icons.freeze_icon.clone());
     let rendered_fire_icon = context.add_image(icons.fire_icon.clone());
     let rendered_snowball_icon = context.add_image(icons.snowball_icon.clone());
+    let rendered_xp_icon = context.add_image(icons.xp_icon.clone());
     commands.insert_resource(SelectedUpgradeIndices::default());
-    commands.insert_resource(UpgradeCards {
-        upgrades: [
-            UpgradeCard {
-                name: "Chestnut Shield".to_string(),
-                icon: rendered_shield_icon,
-                description: "Adds an orbiting chestnut shield that protects you from enemies"
-                    .to_string(),
-                available: true,
-                taken: None,
-                prereq_met: true,
-                upgrade_id: 0,
-            },
-            UpgradeCard {
-                name: "Projectile Rate of Fire".to_string(),
-                icon: rendered_projectile_icon,
-                description: "Increases your attack speed".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: true,
-                upgrade_id: 1,
-            },
-            UpgradeCard {
-                name: "Projectile Speed".to_string(),
-                icon: rendered_projectile_icon,
-                description: "Increases your projectile speed".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: true,
-                upgrade_id: 2,
-            },
-            UpgradeCard {
-                name: "Damage".to_string(),
-                icon: rendered_projectile_icon,
-                description: "Increases your projectile damage".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: true,
-                upgrade_id: 3,
-            },
-            UpgradeCard {
-                name: "Acceleration".to_string(),
-                icon: rendered_projectile_icon,
-                description: "Increases your movement speed".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: true,
-                upgrade_id: 4,
-            },
-            UpgradeCard {
-                name: "Shield Damage".to_string(),
-                icon: rendered_shield_icon,
-                description: "Increases damage dealt by your orbiting shields".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: if player_stats.num_shields > 0 {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 5,
-            },
-            UpgradeCard {
-                name: "Shield Rotation Speed".to_string(),
-                icon: rendered_shield_icon,
-                description: "Makes your shields rotate faster".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: if player_stats.num_shields > 0 {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 6,
-            },
-            //UpgradeCard {
-            //    name: "Projectile Bounces".to_string(),
-            //    icon: rendered_projectile_icon,
-            //    description: "Your projectiles bounce one more time".to_string(),
-            //    available: true,
-            //    taken: None,
-            //},
-            UpgradeCard {
-                name: "Freeze Chance".to_string(),
-                icon: rendered_freeze_icon,
-                description: "Increases chance to freeze enemies".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: true,
-                upgrade_id: 7,
-            },
-            UpgradeCard {
-                name: "Freeze Duration".to_string(),
-                icon: rendered_freeze_icon,
-                description: "Increases how long enemies stay frozen".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: if player_stats.freeze_chance > 0 {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 8,
-            },
-            UpgradeCard {
-                name: "Fire Chance".to_string(),
-                icon: rendered_fire_icon,
-                description: "Increases chance to burn enemies".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: true,
-                upgrade_id: 9,
-            },
-            UpgradeCard {
-                name: "Fire Duration".to_string(),
-                icon: rendered_fire_icon,
-                description: "Increases how long enemies stay burning".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: if player_stats.fire_chance > 0 {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 10,
-            },
-            UpgradeCard {
-                name: "Fire Damage".to_string(),
-                icon: rendered_fire_icon,
-                description: "Increases damage over time from burning".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: if player_stats.fire_chance > 0 {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 11,
-            },
-            UpgradeCard {
-                name: "Flash Freeze".to_string(),
-                icon: rendered_freeze_icon,
-                description: "Freezing burning enemies deals percent damage".to_string(),
-                available: true,
-                taken: Some(false),
-                prereq_met: if player_stats.freeze_chance > 0 && player_stats.fire_chance > 0 {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 12,
-            },
-            UpgradeCard {
-                name: "Flash Freeze Damage".to_string(),
-                icon: rendered_freeze_icon,
-                description: "Increases percent damage from Flash Freeze".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: if player_stats.flash_freeze {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 13,
-            },
-            UpgradeCard {
-                name: "Freezer Burn".to_string(),
-                icon: rendered_freeze_icon,
-                description: "Burning frozen enemies makes them vulnerable".to_string(),
-                available: true,
-                taken: Some(false),
-                prereq_met: if player_stats.freeze_chance > 0 && player_stats.fire_chance > 0 {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 14,
-            },
-            UpgradeCard {
-                name: "Freezer Burn Duration".to_string(),
-                icon: rendered_freeze_icon,
-                description: "Increases vulnerability duration from Freezer Burn".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: if player_stats.freezer_burn {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 15,
-            },
-            UpgradeCard {
-                name: "Freezer Burn Multiplier".to_string(),
-                icon: rendered_freeze_icon,
-                description: "Increases damage multiplier from Freezer Burn".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: if player_stats.freezer_burn {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 16,
-            },
-            UpgradeCard {
-                name: "Pierce".to_string(),
-                icon: rendered_pierce_icon,
-                description: "Your projectiles pierce through one more enemy".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: true,
-                upgrade_id: 17,
-            },
-            UpgradeCard {
-                name: "Shields apply effects".to_string(),
-                icon: rendered_shield_icon,
-                description: "Your shields apply effects (fire and freeze) to enemies".to_string(),
-                available: true,
-                taken: Some(false),
-                prereq_met: if player_stats.num_shields > 0
-                    && (player_stats.freeze_chance > 0 || player_stats.fire_chance > 0)
-                {
-                    true
-                } else {
-                    false
-                },
-                upgrade_id: 18,
-            },
-            UpgradeCard {
-                name: "Sugar Rush Damage Multiplier".to_string(),
-                icon: rendered_snowball_icon,
-                description: "Increases damage of your snowball during sugar rushes (go full speed for max damage)!".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: true,
-                upgrade_id: 19,
-            },
-            UpgradeCard {
-                name: "Knockback Strength".to_string(),
-                icon: rendered_snowball_icon,
-                description: "Increases knockback strength".to_string(),
-                available: true,
-                taken: None,
-                prereq_met: true,
-                upgrade_id: 20,
-            },
-        ],
-    });
+    commands.insert_resource(TakenUpgrades::default());
+    commands.insert_resource(UpgradeCharges::default());
+    commands.insert_resource(ChestReveal::default());
+    let mut upgrades = vec![
+        UpgradeCard {
+            name: "Chestnut Shield".to_string(),
+            icon: rendered_shield_icon,
+            image: icons.shield_icon.clone(),
+            description: "Adds an orbiting chestnut shield that protects you from enemies"
+                .to_string(),
+            max_level: 4,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::CHESTNUT_SHIELD,
+        },
+        UpgradeCard {
+            name: "Projectile Rate of Fire".to_string(),
+            icon: rendered_projectile_icon,
+            image: icons.projectile_icon.clone(),
+            description: "Increases your attack speed".to_string(),
+            max_level: 5,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::RATE_OF_FIRE,
+        },
+        UpgradeCard {
+            name: "Projectile Speed".to_string(),
+            icon: rendered_projectile_icon,
+            image: icons.projectile_icon.clone(),
+            description: "Increases your projectile speed".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::PROJECTILE_SPEED,
+        },
+        UpgradeCard {
+            name: "Damage".to_string(),
+            icon: rendered_projectile_icon,
+            image: icons.projectile_icon.clone(),
+            description: "Increases your projectile damage".to_string(),
+            max_level: 5,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::DAMAGE,
+        },
+        UpgradeCard {
+            name: "Acceleration".to_string(),
+            icon: rendered_projectile_icon,
+            image: icons.projectile_icon.clone(),
+            description: "Increases your movement speed".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::ACCELERATION,
+        },
+        UpgradeCard {
+            name: "Shield Damage".to_string(),
+            icon: rendered_shield_icon,
+            image: icons.shield_icon.clone(),
+            description: "Increases damage dealt by your orbiting shields".to_string(),
+            max_level: 5,
+            level: 0,
+            prereq_met: player_stats.num_shields > 0,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::SHIELD_DAMAGE,
+        },
+        UpgradeCard {
+            name: "Shield Rotation Speed".to_string(),
+            icon: rendered_shield_icon,
+            image: icons.shield_icon.clone(),
+            description: "Makes your shields rotate faster".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: player_stats.num_shields > 0,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::SHIELD_ROTATION_SPEED,
+        },
+        //UpgradeCard {
+        //    name: "Projectile Bounces".to_string(),
+        //    icon: rendered_projectile_icon,
+        //    description: "Your projectiles bounce one more time".to_string(),
+        //    taken: None,
+        //},
+        UpgradeCard {
+            name: "Freeze Chance".to_string(),
+            icon: rendered_freeze_icon,
+            image: icons.freeze_icon.clone(),
+            description: "Increases chance to freeze enemies".to_string(),
+            max_level: 4,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::FREEZE_CHANCE,
+        },
+        UpgradeCard {
+            name: "Freeze Duration".to_string(),
+            icon: rendered_freeze_icon,
+            image: icons.freeze_icon.clone(),
+            description: "Increases how long enemies stay frozen".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: player_stats.freeze_chance > 0,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::FREEZE_DURATION,
+        },
+        UpgradeCard {
+            name: "Fire Chance".to_string(),
+            icon: rendered_fire_icon,
+            image: icons.fire_icon.clone(),
+            description: "Increases chance to burn enemies".to_string(),
+            max_level: 4,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::FIRE_CHANCE,
+        },
+        UpgradeCard {
+            name: "Fire Duration".to_string(),
+            icon: rendered_fire_icon,
+            image: icons.fire_icon.clone(),
+            description: "Increases how long enemies stay burning".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: player_stats.fire_chance > 0,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::FIRE_DURATION,
+        },
+        UpgradeCard {
+            name: "Fire Damage".to_string(),
+            icon: rendered_fire_icon,
+            image: icons.fire_icon.clone(),
+            description: "Increases damage over time from burning".to_string(),
+            max_level: 4,
+            level: 0,
+            prereq_met: player_stats.fire_chance > 0,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::FIRE_DAMAGE,
+        },
+        UpgradeCard {
+            name: "Flash Freeze".to_string(),
+            icon: rendered_freeze_icon,
+            image: icons.freeze_icon.clone(),
+            description: "Freezing burning enemies deals percent damage".to_string(),
+            max_level: 1,
+            level: 0,
+            prereq_met: player_stats.freeze_chance > 0 && player_stats.fire_chance > 0,
+            rarity: Rarity::Epic,
+            upgrade_id: upgrade_ids::FLASH_FREEZE,
+        },
+        UpgradeCard {
+            name: "Flash Freeze Damage".to_string(),
+            icon: rendered_freeze_icon,
+            image: icons.freeze_icon.clone(),
+            description: "Increases percent damage from Flash Freeze".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: player_stats.flash_freeze,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::FLASH_FREEZE_DAMAGE,
+        },
+        UpgradeCard {
+            name: "Freezer Burn".to_string(),
+            icon: rendered_freeze_icon,
+            image: icons.freeze_icon.clone(),
+            description: "Burning frozen enemies makes them vulnerable".to_string(),
+            max_level: 1,
+            level: 0,
+            prereq_met: player_stats.freeze_chance > 0 && player_stats.fire_chance > 0,
+            rarity: Rarity::Epic,
+            upgrade_id: upgrade_ids::FREEZER_BURN,
+        },
+        UpgradeCard {
+            name: "Freezer Burn Duration".to_string(),
+            icon: rendered_freeze_icon,
+            image: icons.freeze_icon.clone(),
+            description: "Increases vulnerability duration from Freezer Burn".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: player_stats.freezer_burn,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::FREEZER_BURN_DURATION,
+        },
+        UpgradeCard {
+            name: "Freezer Burn Multiplier".to_string(),
+            icon: rendered_freeze_icon,
+            image: icons.freeze_icon.clone(),
+            description: "Increases damage multiplier from Freezer Burn".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: player_stats.freezer_burn,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::FREEZER_BURN_MULTIPLIER,
+        },
+        UpgradeCard {
+            name: "Pierce".to_string(),
+            icon: rendered_pierce_icon,
+            image: icons.pierce_icon.clone(),
+            description: "Your projectiles pierce through one more enemy".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::PIERCE,
+        },
+        UpgradeCard {
+            name: "Shields apply effects".to_string(),
+            icon: rendered_shield_icon,
+            image: icons.shield_icon.clone(),
+            description: "Your shields apply effects (fire and freeze) to enemies".to_string(),
+            max_level: 1,
+            level: 0,
+            prereq_met: player_stats.num_shields > 0
+                && (player_stats.freeze_chance > 0 || player_stats.fire_chance > 0),
+            rarity: Rarity::Rare,
+            upgrade_id: upgrade_ids::SHIELDS_APPLY_EFFECTS,
+        },
+        UpgradeCard {
+            name: "Sugar Rush Damage Multiplier".to_string(),
+            icon: rendered_snowball_icon,
+            image: icons.snowball_icon.clone(),
+            description: "Increases damage of your snowball during sugar rushes (go full speed for max damage)!".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::SUGAR_RUSH_DAMAGE,
+        },
+        UpgradeCard {
+            name: "Knockback Strength".to_string(),
+            icon: rendered_snowball_icon,
+            image: icons.snowball_icon.clone(),
+            description: "Increases knockback strength".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::KNOCKBACK_STRENGTH,
+        },
+        UpgradeCard {
+            name: "Candy Cane Shurikens".to_string(),
+            icon: rendered_projectile_icon,
+            image: icons.projectile_icon.clone(),
+            description: "Left click to throw candy cane shurikens".to_string(),
+            max_level: 1,
+            level: 0,
+            prereq_met: !player_stats.shurikens,
+            rarity: Rarity::Rare,
+            upgrade_id: upgrade_ids::CANDY_CANE_SHURIKENS,
+        },
+        UpgradeCard {
+            name: "Dash Charges".to_string(),
+            icon: rendered_snowball_icon,
+            image: icons.snowball_icon.clone(),
+            description: "Store one more dash".to_string(),
+            max_level: 2,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::DASH_CHARGES,
+        },
+        UpgradeCard {
+            name: "Dash Distance".to_string(),
+            icon: rendered_snowball_icon,
+            image: icons.snowball_icon.clone(),
+            description: "Your dash carries you further".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::DASH_DISTANCE,
+        },
+        UpgradeCard {
+            name: "Frost Trail".to_string(),
+            icon: rendered_freeze_icon,
+            image: icons.freeze_icon.clone(),
+            description: "Dashing leaves a trail that freezes enemies who cross it".to_string(),
+            max_level: 1,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Rare,
+            upgrade_id: upgrade_ids::FROST_TRAIL,
+        },
+        UpgradeCard {
+            name: "Critical Chance".to_string(),
+            icon: rendered_projectile_icon,
+            image: icons.projectile_icon.clone(),
+            description: "Increases your chance to land critical hits".to_string(),
+            max_level: 4,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::CRITICAL_CHANCE,
+        },
+        UpgradeCard {
+            name: "Critical Damage".to_string(),
+            icon: rendered_projectile_icon,
+            image: icons.projectile_icon.clone(),
+            description: "Critical hits deal more damage. Crits shatter frozen enemies"
+                .to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::CRITICAL_DAMAGE,
+        },
+        UpgradeCard {
+            name: "Chill Chance".to_string(),
+            icon: rendered_freeze_icon,
+            image: icons.freeze_icon.clone(),
+            description: "Chance to chill enemies, slowing them until enough stacks freeze them solid".to_string(),
+            max_level: 4,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::CHILL_CHANCE,
+        },
+        UpgradeCard {
+            name: "Chill Duration".to_string(),
+            icon: rendered_freeze_icon,
+            image: icons.freeze_icon.clone(),
+            description: "Increases how long chill stacks last".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::CHILL_DURATION,
+        },
+        UpgradeCard {
+            name: "Shock Chance".to_string(),
+            icon: rendered_pierce_icon,
+            image: icons.pierce_icon.clone(),
+            description: "Chance to shock enemies, arcing damage to those nearby".to_string(),
+            max_level: 4,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::SHOCK_CHANCE,
+        },
+        UpgradeCard {
+            name: "Shock Chains".to_string(),
+            icon: rendered_pierce_icon,
+            image: icons.pierce_icon.clone(),
+            description: "Shocks arc to one more enemy and deal more damage".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::SHOCK_CHAINS,
+        },
+        UpgradeCard {
+            name: "Peppermint Poison".to_string(),
+            icon: rendered_projectile_icon,
+            image: icons.projectile_icon.clone(),
+            description: "Chance to poison enemies; poison stacks and spreads when they die".to_string(),
+            max_level: 4,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::PEPPERMINT_POISON,
+        },
+        UpgradeCard {
+            name: "Poison Potency".to_string(),
+            icon: rendered_projectile_icon,
+            image: icons.projectile_icon.clone(),
+            description: "Poison deals more damage and stacks higher".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::POISON_POTENCY,
+        },
+        UpgradeCard {
+            name: "Lingering Peppermint".to_string(),
+            icon: rendered_projectile_icon,
+            image: icons.projectile_icon.clone(),
+            description: "Poison lasts longer".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::LINGERING_PEPPERMINT,
+        },
+        UpgradeCard {
+            name: "Superconduct".to_string(),
+            icon: rendered_pierce_icon,
+            image: icons.pierce_icon.clone(),
+            description: "Shocking chilled enemies deals bonus damage per chill stack".to_string(),
+            max_level: 1,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Legendary,
+            upgrade_id: upgrade_ids::SUPERCONDUCT,
+        },
+        UpgradeCard {
+            name: "Toxic Flare".to_string(),
+            icon: rendered_fire_icon,
+            image: icons.fire_icon.clone(),
+            description: "Burning poisoned enemies detonates all of their poison".to_string(),
+            max_level: 1,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Legendary,
+            upgrade_id: upgrade_ids::TOXIC_FLARE,
+        },
+        UpgradeCard {
+            name: "Lucky Star".to_string(),
+            icon: rendered_xp_icon,
+            image: icons.xp_icon.clone(),
+            description: "Rarer upgrades show up more often".to_string(),
+            max_level: 3,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Rare,
+            upgrade_id: upgrade_ids::LUCKY_STAR,
+        },
+        UpgradeCard {
+            name: "Sweet Tooth".to_string(),
+            icon: rendered_xp_icon,
+            image: icons.xp_icon.clone(),
+            description: "Pick up XP and items from further away".to_string(),
+            max_level: 4,
+            level: 0,
+            prereq_met: true,
+            rarity: Rarity::Common,
+            upgrade_id: upgrade_ids::SWEET_TOOTH,
+        },
+    ];
+    // Evolutions come last, built from their definitions
+    for (i, def) in EVOLUTIONS.iter().enumerate() {
+        let image = asset_server.load(def.sprite);
+        upgrades.push(UpgradeCard {
+            name: def.name.to_string(),
+            icon: context.add_image(image.clone()),
+            image,
+            description: def.description.to_string(),
+            max_level: 1,
+            level: 0,
+            prereq_met: false,
+            rarity: Rarity::Legendary,
+            upgrade_id: EVOLUTION_ID_BASE + i as u32,
+        });
+    }
+    commands.insert_resource(UpgradeCards { upgrades });
 }
 
 fn load_fonts(mut context: EguiContexts) {
@@ -369,10 +595,13 @@ fn setup_tutorial(mut contexts: EguiContexts, mut app_state: ResMut<NextState<Ap
                 ui.add_space(20.0);
 
                 ui.label(
-                    egui::RichText::new("• Your snowball grows as you defeat enemies").size(24.0),
+                    egui::RichText::new(
+                        "• Your snowball grows as you roll over fresh snow and defeat enemies",
+                    )
+                    .size(24.0),
                 );
                 ui.label(
-                    egui::RichText::new("  Don't let it get too small or it's game over!")
+                    egui::RichText::new("  Standing still or touching burning enemies melts it!")
                         .size(24.0),
                 );
                 ui.add_space(20.0);
@@ -387,6 +616,12 @@ fn setup_tutorial(mut contexts: EguiContexts, mut app_state: ResMut<NextState<Ap
                 );
                 ui.add_space(20.0);
 
+                ui.label(
+                    egui::RichText::new("• Press Space to dash through enemies unharmed")
+                        .size(24.0),
+                );
+                ui.add_space(20.0);
+
                 ui.label(
                     egui::RichText::new(
                         "• Collect candy canes on the ground to activate Sugar Rush",
@@ -422,7 +657,6 @@ fn setup_tutorial(mut contexts: EguiContexts, mut app_state: ResMut<NextState<Ap
 fn setup_main_menu(
     mut contexts: EguiContexts,
     mut app_state: ResMut<NextState<AppState>>,
-    mut game_state: ResMut<NextState<GameState>>,
     logo_image: Local<LogoImage>,
 ) {
     let logo_image = contexts.add_image(logo_image.logo.clone());
@@ -449,8 +683,7 @@ fn setup_main_menu(
                     .on_hover_text("Start your adventure!")
                     .clicked()
                 {
-                    app_state.set(AppState::InGame);
-                    game_state.set(GameState::Playing);
+                    app_state.set(AppState::CharacterSelect);
                 }
 
                 ui.add_space(30.0);
@@ -513,10 +746,97 @@ fn setup_main_menu(
             });
         });
 }
+fn setup_character_select(
+    mut contexts: EguiContexts,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut selected_character: ResMut<SelectedCharacter>,
+    progress: Res<Progress>,
+) {
+    egui::CentralPanel::default()
+        .frame(egui::Frame::none().fill(egui::Color32::from_rgba_premultiplied(0, 0, 0, 250)))
+        .show(contexts.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(40.0);
+                ui.label(egui::RichText::new("Choose a Rider").size(48.0).strong());
+                ui.add_space(30.0);
+
+                ui.horizontal(|ui| {
+                    ui.add_space(
+                        ui.available_width() / 2.0 - (260.0 * CHARACTERS.len() as f32) / 2.0,
+                    );
+
+                    for (index, character) in CHARACTERS.iter().enumerate() {
+                        let unlocked = character.is_unlocked(&progress);
+                        ui.group(|ui| {
+                            ui.set_min_size(egui::vec2(240.0, 300.0));
+                            ui.set_max_size(egui::vec2(240.0, 300.0));
+                            ui.vertical_centered(|ui| {
+                                ui.add_space(10.0);
+                                ui.label(egui::RichText::new(character.name).size(28.0).strong());
+                                ui.add_space(10.0);
+                                ui.label(egui::RichText::new(character.description).size(16.0));
+                                ui.add_space(10.0);
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "Passive: {}",
+                                        character.passive_description()
+                                    ))
+                                    .size(16.0),
+                                );
+                                ui.add_space(15.0);
+
+                                if unlocked {
+                                    if ui
+                                        .button(
+                                            egui::RichText::new("Select")
+                                                .size(20.0)
+                                                .color(egui::Color32::WHITE),
+                                        )
+                                        .clicked()
+                                    {
+                                        selected_character.0 = index;
+                                        app_state.set(AppState::InGame);
+                                        game_state.set(GameState::Playing);
+                                    }
+                                } else {
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "Locked: {}",
+                                            character.unlock_description()
+                                        ))
+                                        .size(16.0)
+                                        .color(egui::Color32::from_rgb(150, 150, 150)),
+                                    );
+                                }
+                            });
+                        });
+                    }
+                });
+
+                ui.add_space(40.0);
+                if ui
+                    .add_sized(
+                        [220.0, 60.0],
+                        egui::Button::new(
+                            egui::RichText::new("Back")
+                                .size(32.0)
+                                .color(egui::Color32::from_rgb(240, 240, 255)),
+                        ),
+                    )
+                    .on_hover_text("Return to main menu")
+                    .clicked()
+                {
+                    app_state.set(AppState::MainMenu);
+                }
+            });
+        });
+}
 fn setup_settings(
     mut contexts: EguiContexts,
     mut app_state: ResMut<NextState<AppState>>,
     mut volume: ResMut<Volume>,
+    mut display_settings: ResMut<DisplaySettings>,
 ) {
     egui::CentralPanel::default()
         .frame(egui::Frame::none().fill(egui::Color32::from_rgba_premultiplied(0, 0, 0, 250)))
@@ -553,6 +873,68 @@ fn setup_settings(
                     ui.add(egui::Slider::new(&mut volume.sfx, 0.0..=1.0).text(""));
                 });
 
+                ui.add_space(20.0);
+
+                // UI Volume Slider
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("UI Volume")
+                            .size(24.0)
+                            .color(egui::Color32::WHITE),
+                    );
+                    ui.add(egui::Slider::new(&mut volume.ui, 0.0..=1.0).text(""));
+                });
+
+                ui.add_space(20.0);
+
+                // Damage Numbers Toggle
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("Damage Numbers")
+                            .size(24.0)
+                            .color(egui::Color32::WHITE),
+                    );
+                    ui.checkbox(&mut display_settings.damage_numbers, "");
+                });
+
+                ui.add_space(20.0);
+
+                // Enemy Health Bars Toggle
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("Enemy Health Bars")
+                            .size(24.0)
+                            .color(egui::Color32::WHITE),
+                    );
+                    ui.checkbox(&mut display_settings.enemy_health_bars, "");
+                });
+
+                ui.add_space(20.0);
+
+                // Screen Shake Slider
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("Screen Shake")
+                            .size(24.0)
+                            .color(egui::Color32::WHITE),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut display_settings.screen_shake, 0.0..=1.0).text(""),
+                    );
+                });
+
+                ui.add_space(20.0);
+
+                // Hit Stop Slider
+                ui.horizontal(|ui| {
+                    ui.label(
+                        egui::RichText::new("Hit Stop")
+                            .size(24.0)
+                            .color(egui::Color32::WHITE),
+                    );
+                    ui.add(egui::Slider::new(&mut display_settings.hit_stop, 0.0..=1.0).text(""));
+                });
+
                 ui.add_space(60.0);
 
                 // Back button with enhanced styling
@@ -580,6 +962,8 @@ fn setup_game_over(
     mut contexts: EguiContexts,
     mut app_state: ResMut<NextState<AppState>>,
     mut game_state: ResMut<NextState<GameState>>,
+    run_stats: Res<RunStats>,
+    upgrades: Res<UpgradeCards>,
 ) {
     egui::CentralPanel::default()
         .frame(egui::Frame::none().fill(egui::Color32::from_rgba_premultiplied(240, 240, 255, 0)))
@@ -594,7 +978,30 @@ fn setup_game_over(
                     .strong();
 
                 ui.add(egui::Label::new(title));
-                ui.add_space(60.0);
+                ui.add_space(20.0);
+
+                ui.label(
+                    egui::RichText::new(format!("Chests opened: {}", run_stats.chests_opened))
+                        .size(20.0),
+                );
+                if !run_stats.chest_rewards.is_empty() {
+                    let rewards: Vec<&str> = run_stats
+                        .chest_rewards
+                        .iter()
+                        .filter_map(|id| {
+                            upgrades
+                                .upgrades
+                                .iter()
+                                .find(|upgrade| upgrade.upgrade_id == *id)
+                        })
+                        .map(|upgrade| upgrade.name.as_str())
+                        .collect();
+                    ui.label(
+                        egui::RichText::new(format!("Chest rewards: {}", rewards.join(", ")))
+                            .size(16.0),
+                    );
+                }
+                ui.add_space(40.0);
 
                 // Retry button
                 let retry_button = egui::Button::new(
@@ -722,89 +1129,425 @@ fn listen_pause(
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rarity {
+    Common,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Rarity {
+    const ALL: [Rarity; 4] = [
+        Rarity::Common,
+        Rarity::Rare,
+        Rarity::Epic,
+        Rarity::Legendary,
+    ];
+
+    /// Draw weight; luck boosts every tier above common, the rarest the most.
+    fn weight(self, luck: f32) -> f32 {
+        let base = match self {
+            Rarity::Common => 60.0,
+            Rarity::Rare => 25.0,
+            Rarity::Epic => 10.0,
+            Rarity::Legendary => 4.0,
+        };
+        base * (1.0 + luck * self as u32 as f32)
+    }
+
+    /// How much stronger a stackable upgrade is at this tier, e.g. +25% damage becomes +60%.
+    fn magnitude(self) -> f32 {
+        match self {
+            Rarity::Common => 1.0,
+            Rarity::Rare => 1.5,
+            Rarity::Epic => 1.9,
+            Rarity::Legendary => 2.4,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Rarity::Common => "Common",
+            Rarity::Rare => "Rare",
+            Rarity::Epic => "Epic",
+            Rarity::Legendary => "Legendary",
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            Rarity::Common => egui::Color32::from_rgb(200, 200, 200),
+            Rarity::Rare => egui::Color32::from_rgb(70, 140, 255),
+            Rarity::Epic => egui::Color32::from_rgb(180, 80, 230),
+            Rarity::Legendary => egui::Color32::from_rgb(255, 170, 30),
+        }
+    }
+
+    fn roll(luck: f32, rng: &mut impl Rng) -> Rarity {
+        *Rarity::ALL
+            .choose_weighted(rng, |rarity| rarity.weight(luck))
+            .unwrap_or(&Rarity::Common)
+    }
+}
+
 struct UpgradeCard {
     name: String,
     icon: egui::TextureId, // Path to icon asset
+    image: Handle<Image>,  // Same icon for the in-game HUD
     description: String,
-    available: bool,
-    taken: Option<bool>,
+    /// One-off upgrades have a max level of 1; maxed upgrades leave the pool
+    max_level: u32,
+    level: u32,
     prereq_met: bool,
+    /// Fixed tier for one-off upgrades; the lowest tier stackable upgrades can roll
+    rarity: Rarity,
     upgrade_id: u32,
 }
-// Start of Selection
 #[derive(Resource)]
 struct UpgradeCards {
-    upgrades: [UpgradeCard; 21],
+    upgrades: Vec<UpgradeCard>,
+}
+
+impl UpgradeCards {
+    fn refresh_prerequisites(&mut self, player_stats: &PlayerStats) {
+        // Evolutions depend on the levels of other upgrades, so check them up front
+        let evolutions_ready: Vec<bool> = EVOLUTIONS
+            .iter()
+            .map(|def| {
+                !player_stats.has_evolution(def.evolution)
+                    && def
+                        .requires
+                        .iter()
+                        .all(|requirement| self.requirement_met(requirement))
+            })
+            .collect();
+
+        // Re-check each upgrade’s “prereq_met” based on current player_stats
+        for upgrade in &mut self.upgrades {
+            let meets_requirements = match upgrade.upgrade_id {
+                upgrade_ids::RATE_OF_FIRE
+                | upgrade_ids::PROJECTILE_SPEED
+                | upgrade_ids::DAMAGE
+                | upgrade_ids::PIERCE => player_stats.shurikens,
+                upgrade_ids::SHIELD_DAMAGE => player_stats.num_shields > 0,
+                upgrade_ids::SHIELD_ROTATION_SPEED => player_stats.num_shields > 0,
+                upgrade_ids::FREEZE_DURATION => player_stats.freeze_chance > 0,
+                upgrade_ids::FIRE_DURATION | upgrade_ids::FIRE_DAMAGE => {
+                    player_stats.fire_chance > 0
+                }
+                upgrade_ids::FLASH_FREEZE => {
+                    player_stats.freeze_chance > 0 && player_stats.fire_chance > 0
+                }
+                upgrade_ids::FLASH_FREEZE_DAMAGE => player_stats.flash_freeze,
+                upgrade_ids::FREEZER_BURN => {
+                    player_stats.freeze_chance > 0 && player_stats.fire_chance > 0
+                }
+                upgrade_ids::FREEZER_BURN_DURATION | upgrade_ids::FREEZER_BURN_MULTIPLIER => {
+                    player_stats.freezer_burn
+                }
+                upgrade_ids::SHIELDS_APPLY_EFFECTS => {
+                    player_stats.num_shields > 0
+                        && (player_stats.fire_chance > 0
+                            || player_stats.freeze_chance > 0
+                            || player_stats.chill_chance > 0
+                            || player_stats.shock_chance > 0
+                            || player_stats.poison_chance > 0)
+                }
+                upgrade_ids::CANDY_CANE_SHURIKENS => !player_stats.shurikens,
+                upgrade_ids::CHILL_DURATION => player_stats.chill_chance > 0,
+                upgrade_ids::SHOCK_CHAINS => player_stats.shock_chance > 0,
+                upgrade_ids::POISON_POTENCY | upgrade_ids::LINGERING_PEPPERMINT => {
+                    player_stats.poison_chance > 0
+                }
+                upgrade_ids::SUPERCONDUCT => {
+                    player_stats.chill_chance > 0 && player_stats.shock_chance > 0
+                }
+                upgrade_ids::TOXIC_FLARE => {
+                    player_stats.poison_chance > 0 && player_stats.fire_chance > 0
+                }
+                id if id >= EVOLUTION_ID_BASE => {
+                    evolutions_ready[(id - EVOLUTION_ID_BASE) as usize]
+                }
+                // etc. for any others that have special requirements
+                _ => true,
+            };
+            upgrade.prereq_met = meets_requirements;
+        }
+    }
+
+    fn requirement_met(&self, requirement: &Requirement) -> bool {
+        let (upgrade_id, maxed) = match *requirement {
+            Requirement::Maxed(upgrade_id) => (upgrade_id, true),
+            Requirement::Taken(upgrade_id) => (upgrade_id, false),
+        };
+        self.upgrades
+            .iter()
+            .find(|upgrade| upgrade.upgrade_id == upgrade_id)
+            .is_some_and(|upgrade| {
+                if maxed {
+                    upgrade.level >= upgrade.max_level
+                } else {
+                    upgrade.level > 0
+                }
+            })
+    }
+
+    /// Draws up to `amount` cards weighted by base rarity, skipping `exclude` and banished ids.
+    fn draw(
+        &self,
+        amount: usize,
+        luck: f32,
+        exclude: &[usize],
+        banished: &[u32],
+    ) -> Vec<(usize, Rarity)> {
+        let mut rng = rand::thread_rng();
+        let available_upgrade_indices: Vec<usize> = self
+            .upgrades
+            .iter()
+            .enumerate()
+            .filter(|(i, u)| {
+                u.prereq_met
+                    && u.level < u.max_level
+                    && !exclude.contains(i)
+                    && !banished.contains(&u.upgrade_id)
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        let selection = available_upgrade_indices
+            .choose_multiple_weighted(&mut rng, amount, |&i| self.upgrades[i].rarity.weight(luck))
+            .map(|chosen| chosen.cloned().collect::<Vec<usize>>())
+            .unwrap_or(available_upgrade_indices);
+
+        // Stackable upgrades roll their tier, one-offs keep their own
+        selection
+            .into_iter()
+            .map(|i| {
+                let upgrade = &self.upgrades[i];
+                let rarity = if upgrade.max_level > 1 {
+                    Rarity::roll(luck, &mut rng).max(upgrade.rarity)
+                } else {
+                    upgrade.rarity
+                };
+                (i, rarity)
+            })
+            .collect()
+    }
+}
+
+/// Ways out of an upgrade draw for this run, plus the upgrades banished with them.
+#[derive(Resource, Default)]
+pub struct UpgradeCharges {
+    pub rerolls: u32,
+    pub skips: u32,
+    pub banishes: u32,
+    banished: Vec<u32>,
+}
+
+impl UpgradeCharges {
+    /// Starting charges, with more unlocked by best results across runs.
+    fn for_run(progress: &Progress) -> Self {
+        Self {
+            rerolls: 1
+                + u32::from(progress.best_level >= 10)
+                + u32::from(progress.best_level >= 20),
+            skips: 1,
+            banishes: 1 + u32::from(progress.best_time >= 300.0),
+            banished: Vec::new(),
+        }
+    }
+}
+
+/// Upgrades taken this run, in the order first taken, shown on the HUD.
+#[derive(Resource, Default)]
+pub struct TakenUpgrades {
+    pub upgrades: Vec<TakenUpgrade>,
+}
+
+pub struct TakenUpgrade {
+    pub upgrade_id: u32,
+    pub image: Handle<Image>,
+    pub count: u32,
+}
+
+impl TakenUpgrades {
+    fn record(&mut self, upgrade: &UpgradeCard) {
+        match self
+            .upgrades
+            .iter_mut()
+            .find(|taken| taken.upgrade_id == upgrade.upgrade_id)
+        {
+            Some(taken) => taken.count += 1,
+            None => self.upgrades.push(TakenUpgrade {
+                upgrade_id: upgrade.upgrade_id,
+                image: upgrade.image.clone(),
+                count: 1,
+            }),
+        }
+    }
 }
 
+/// Cards on offer, with the rarity each was rolled at.
 #[derive(Resource, Default)]
 struct SelectedUpgradeIndices {
-    indices: Vec<usize>,
+    indices: Vec<(usize, Rarity)>,
+}
+
+/// Every upgrade starts back at level 0 for a new run.
+fn reset_upgrades(
+    mut upgrades: ResMut<UpgradeCards>,
+    mut taken_upgrades: ResMut<TakenUpgrades>,
+    mut upgrade_charges: ResMut<UpgradeCharges>,
+    progress: Res<Progress>,
+) {
+    taken_upgrades.upgrades.clear();
+    *upgrade_charges = UpgradeCharges::for_run(&progress);
+    for upgrade in &mut upgrades.upgrades {
+        upgrade.level = 0;
+    }
 }
 
 fn generate_available_upgrades(
     mut selected_indices_res: ResMut<SelectedUpgradeIndices>,
     mut upgrades: ResMut<UpgradeCards>,
     player_stats: Res<PlayerStats>,
+    upgrade_charges: Res<UpgradeCharges>,
 ) {
-    // 1) Re-check each upgrade’s “prereq_met” based on current player_stats
-    for upgrade in &mut upgrades.upgrades {
-        let meets_requirements = match upgrade.upgrade_id {
-            5 => player_stats.num_shields > 0,
-            6 => player_stats.num_shields > 0,
-            8 => player_stats.freeze_chance > 0,
-            10 | 11 => player_stats.fire_chance > 0,
-            12 => player_stats.freeze_chance > 0 && player_stats.fire_chance > 0,
-            13 => player_stats.flash_freeze,
-            14 => player_stats.freeze_chance > 0 && player_stats.fire_chance > 0,
-            15 | 16 => player_stats.freezer_burn,
-            18 => {
-                player_stats.num_shields > 0
-                    && (player_stats.fire_chance > 0 || player_stats.freeze_chance > 0)
-            }
-            // etc. for any others that have special requirements
-            _ => true,
-        };
-        upgrade.prereq_met = meets_requirements;
+    upgrades.refresh_prerequisites(&player_stats);
+
+    // Draw a fresh set of 3
+    selected_indices_res.indices =
+        upgrades.draw(3, player_stats.luck, &[], &upgrade_charges.banished);
+}
+
+/// Applies an upgrade's effect at the rarity it was rolled at.
+fn apply_upgrade(stats: &mut PlayerStats, upgrade_id: u32, rarity: Rarity) {
+    let m = rarity.magnitude();
+    // Whole-number bonuses only grow at legendary
+    let count = if rarity == Rarity::Legendary { 2 } else { 1 };
+    let chance = |base: f32| (base * m).round() as i32;
+    match upgrade_id {
+        upgrade_ids::CHESTNUT_SHIELD => stats.num_shields += count,
+        // Closes in on the floor instead of shrinking towards firing every frame
+        upgrade_ids::RATE_OF_FIRE => {
+            stats.rate_of_fire =
+                MIN_RATE_OF_FIRE + (stats.rate_of_fire - MIN_RATE_OF_FIRE).max(0.) / (1. + m);
+        }
+        upgrade_ids::PROJECTILE_SPEED => stats.projectile_speed *= 1. + m,
+        upgrade_ids::DAMAGE => stats.damage *= 1. + 0.25 * m,
+        upgrade_ids::ACCELERATION => stats.acceleration_rate *= 1. + 0.5 * m,
+        upgrade_ids::SHIELD_DAMAGE => stats.shield_damage *= 1. + 0.25 * m,
+        upgrade_ids::SHIELD_ROTATION_SPEED => stats.shield_rotation_speed += 0.02 * m,
+        upgrade_ids::FREEZE_CHANCE => stats.freeze_chance += chance(20.),
+        upgrade_ids::FREEZE_DURATION => stats.freeze_duration += 1. * m,
+        upgrade_ids::FIRE_CHANCE => stats.fire_chance += chance(20.),
+        upgrade_ids::FIRE_DURATION => stats.fire_duration += 2.0 * m,
+        upgrade_ids::FIRE_DAMAGE => stats.fire_dps *= 1. + 0.5 * m,
+        upgrade_ids::FLASH_FREEZE => stats.flash_freeze = true,
+        upgrade_ids::FLASH_FREEZE_DAMAGE => stats.flash_freeze_percent_damage += 0.1 * m,
+        upgrade_ids::FREEZER_BURN => stats.freezer_burn = true,
+        upgrade_ids::FREEZER_BURN_DURATION => stats.freezer_burn_duration += 1. * m,
+        upgrade_ids::FREEZER_BURN_MULTIPLIER => stats.freezer_burn_multiplier *= 1. + 0.5 * m,
+        upgrade_ids::PIERCE => stats.projectile_piercing += count as i32,
+        upgrade_ids::SHIELDS_APPLY_EFFECTS => stats.shield_apply_effects = true,
+        upgrade_ids::SUGAR_RUSH_DAMAGE => stats.snowball_damage_multiplier += 0.5 * m,
+        upgrade_ids::KNOCKBACK_STRENGTH => stats.knockback_strength += 1.0 * m,
+        upgrade_ids::DASH_CHARGES => stats.dash_charges += count,
+        upgrade_ids::DASH_DISTANCE => stats.dash_distance *= 1. + 0.25 * m,
+        upgrade_ids::FROST_TRAIL => stats.dash_freeze_trail = true,
+        upgrade_ids::CANDY_CANE_SHURIKENS => stats.shurikens = true,
+        upgrade_ids::CRITICAL_CHANCE => stats.crit_chance += chance(10.),
+        upgrade_ids::CRITICAL_DAMAGE => stats.crit_multiplier += 0.5 * m,
+        upgrade_ids::CHILL_CHANCE => stats.chill_chance += chance(20.),
+        upgrade_ids::CHILL_DURATION => stats.chill_duration += 1. * m,
+        upgrade_ids::SHOCK_CHANCE => stats.shock_chance += chance(15.),
+        upgrade_ids::SHOCK_CHAINS => {
+            stats.shock_chains += count as usize;
+            stats.shock_damage *= 1. + 0.25 * m;
+        }
+        upgrade_ids::PEPPERMINT_POISON => stats.poison_chance += chance(20.),
+        upgrade_ids::POISON_POTENCY => {
+            stats.poison_dps *= 1. + 0.5 * m;
+            stats.poison_max_stacks += count;
+        }
+        upgrade_ids::SUPERCONDUCT => stats.superconduct = true,
+        upgrade_ids::TOXIC_FLARE => stats.toxic_flare = true,
+        upgrade_ids::LUCKY_STAR => stats.luck += 0.25 * m,
+        upgrade_ids::SWEET_TOOTH => stats.pickup_radius += 20. * m,
+        upgrade_ids::LINGERING_PEPPERMINT => stats.poison_duration += 1.5 * m,
+        id if id >= EVOLUTION_ID_BASE => EVOLUTIONS[(id - EVOLUTION_ID_BASE) as usize]
+            .evolution
+            .apply(stats),
+
+        _ => {}
     }
+}
 
-    // 2) Clear out any old selection first
-    selected_indices_res.indices.clear();
-
-    // 3) Collect the indices of available (and now up-to-date) upgrades
-    let mut rng = rand::thread_rng();
-    let available_upgrade_indices: Vec<usize> = upgrades
-        .upgrades
-        .iter()
-        .enumerate()
-        .filter(|(_, u)| u.available && u.prereq_met)
-        .map(|(i, _)| i)
-        .collect();
-
-    // 4) If at least 3 are available, choose 3 at random. Otherwise, pick all.
-    let new_selection = if available_upgrade_indices.len() >= 3 {
-        available_upgrade_indices
-            .choose_multiple(&mut rng, 3)
-            .cloned()
-            .collect::<Vec<usize>>()
-    } else {
-        available_upgrade_indices
+/// The stat an upgrade changes, formatted for the before and after preview on its card.
+fn upgrade_stat(stats: &PlayerStats, upgrade_id: u32) -> Option<String> {
+    let stat = match upgrade_id {
+        upgrade_ids::CHESTNUT_SHIELD => format!("{} shields", stats.num_shields),
+        upgrade_ids::RATE_OF_FIRE => format!("{:.2}s", stats.rate_of_fire),
+        upgrade_ids::PROJECTILE_SPEED => format!("{:.0}", stats.projectile_speed),
+        upgrade_ids::DAMAGE => format!("{:.0}", stats.damage),
+        upgrade_ids::ACCELERATION => format!("{:.0}", stats.acceleration_rate),
+        upgrade_ids::SHIELD_DAMAGE => format!("{:.0}", stats.shield_damage),
+        upgrade_ids::SHIELD_ROTATION_SPEED => format!("{:.3}", stats.shield_rotation_speed),
+        upgrade_ids::FREEZE_CHANCE => format!("{}%", stats.freeze_chance),
+        upgrade_ids::FREEZE_DURATION => format!("{:.1}s", stats.freeze_duration),
+        upgrade_ids::FIRE_CHANCE => format!("{}%", stats.fire_chance),
+        upgrade_ids::FIRE_DURATION => format!("{:.1}s", stats.fire_duration),
+        upgrade_ids::FIRE_DAMAGE => format!("{:.1}/s", stats.fire_dps),
+        upgrade_ids::FLASH_FREEZE_DAMAGE => {
+            format!("{:.0}%", stats.flash_freeze_percent_damage * 100.0)
+        }
+        upgrade_ids::FREEZER_BURN_DURATION => format!("{:.1}s", stats.freezer_burn_duration),
+        upgrade_ids::FREEZER_BURN_MULTIPLIER => format!("x{:.2}", stats.freezer_burn_multiplier),
+        upgrade_ids::PIERCE => format!("{} pierce", stats.projectile_piercing),
+        upgrade_ids::SUGAR_RUSH_DAMAGE => format!("x{:.2}", stats.snowball_damage_multiplier),
+        upgrade_ids::KNOCKBACK_STRENGTH => format!("{:.1}", stats.knockback_strength),
+        upgrade_ids::DASH_CHARGES => format!("{} charges", stats.dash_charges),
+        upgrade_ids::DASH_DISTANCE => format!("{:.0}", stats.dash_distance),
+        upgrade_ids::CRITICAL_CHANCE => format!("{}%", stats.crit_chance),
+        upgrade_ids::CRITICAL_DAMAGE => format!("x{:.2}", stats.crit_multiplier),
+        upgrade_ids::CHILL_CHANCE => format!("{}%", stats.chill_chance),
+        upgrade_ids::CHILL_DURATION => format!("{:.1}s", stats.chill_duration),
+        upgrade_ids::SHOCK_CHANCE => format!("{}%", stats.shock_chance),
+        upgrade_ids::SHOCK_CHAINS => {
+            format!("{} chains, {:.0}", stats.shock_chains, stats.shock_damage)
+        }
+        upgrade_ids::PEPPERMINT_POISON => format!("{}%", stats.poison_chance),
+        upgrade_ids::POISON_POTENCY => format!(
+            "{:.1}/s, {} stacks",
+            stats.poison_dps, stats.poison_max_stacks
+        ),
+        upgrade_ids::LUCKY_STAR => format!("{:.2}", stats.luck),
+        upgrade_ids::SWEET_TOOTH => format!("{:.0}px", stats.pickup_radius),
+        upgrade_ids::LINGERING_PEPPERMINT => format!("{:.1}s", stats.poison_duration),
+        // One-off upgrades unlock something rather than change a number
+        _ => return None,
     };
-
-    // 5) Store them in the resource for later
-    selected_indices_res.indices = new_selection;
+    Some(stat)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn upgrade_screen(
     mut egui_ctx: EguiContexts,
     mut app_state: ResMut<NextState<GameState>>,
     mut player_stats: ResMut<PlayerStats>,
     mut upgrades: ResMut<UpgradeCards>,
-    selected_indices_res: Res<SelectedUpgradeIndices>,
+    mut taken_upgrades: ResMut<TakenUpgrades>,
+    mut selected_indices_res: ResMut<SelectedUpgradeIndices>,
+    mut upgrade_charges: ResMut<UpgradeCharges>,
+    mut q_player: Query<(&mut PlayerHealth, &mut PlayerXp), With<Player>>,
+    mut sfx: EventWriter<SfxEvent>,
 ) {
     let ctx = egui_ctx.ctx_mut();
     // Get the indices chosen by generate_available_upgrades
-    let selected_indices = &selected_indices_res.indices;
+    let selected_indices = selected_indices_res.indices.clone();
+    let mut reroll = false;
+    let mut banish_slot = None;
 
     egui::CentralPanel::default()
         .frame(egui::Frame::none().fill(egui::Color32::from_rgba_premultiplied(0, 0, 0, 250)))
@@ -814,75 +1557,327 @@ fn upgrade_screen(
                 ui.label(egui::RichText::new("Choose an Upgrade").size(48.0).strong());
                 ui.add_space(15.0);
 
+                ui.horizontal(|ui| {
+                    ui.add_space(ui.available_width() / 2.0 - 160.0);
+                    if ui
+                        .add_enabled(
+                            upgrade_charges.rerolls > 0,
+                            egui::Button::new(
+                                egui::RichText::new(format!(
+                                    "Reroll ({})",
+                                    upgrade_charges.rerolls
+                                ))
+                                .size(20.0),
+                            )
+                            .min_size(egui::vec2(150.0, 40.0)),
+                        )
+                        .on_hover_text("Draw three new upgrades")
+                        .clicked()
+                    {
+                        reroll = true;
+                    }
+                    ui.add_space(20.0);
+                    let Ok((mut player_health, mut player_xp)) = q_player.get_single_mut() else {
+                        return;
+                    };
+                    // Skipping heals when hurt, otherwise it gives a chunk of XP
+                    let hurt = player_health.hp < PLAYER_START_HP;
+                    if ui
+                        .add_enabled(
+                            upgrade_charges.skips > 0,
+                            egui::Button::new(
+                                egui::RichText::new(format!("Skip ({})", upgrade_charges.skips))
+                                    .size(20.0),
+                            )
+                            .min_size(egui::vec2(150.0, 40.0)),
+                        )
+                        .on_hover_text(if hurt {
+                            format!("Heal {} HP instead", SKIP_HEAL)
+                        } else {
+                            "Take some XP instead".to_string()
+                        })
+                        .clicked()
+                    {
+                        upgrade_charges.skips -= 1;
+                        if hurt {
+                            player_health.hp = (player_health.hp + SKIP_HEAL).min(PLAYER_START_HP);
+                        } else {
+                            let requirement = player_xp.requirement();
+                            player_xp.xp += requirement * SKIP_XP_FRACTION;
+                        }
+                        app_state.set(GameState::Playing);
+                    }
+                });
+                ui.add_space(15.0);
+
                 ui.horizontal_centered(|ui| {
                     // (Optional) Position the 3 upgrade cards in the center
-                    ui.add_space(ui.available_width() / 2.0 - (300.0 * 3 as f32) / 2.0);
+                    ui.add_space(ui.available_width() / 2.0 - (300.0 * 3_f32) / 2.0);
 
-                    for &index in selected_indices {
+                    for (slot, &(index, rarity)) in selected_indices.iter().enumerate() {
                         let upgrade = &mut upgrades.upgrades[index];
-                        ui.group(|ui| {
-                            ui.set_min_size(egui::vec2(300.0, 400.0));
-                            ui.set_max_size(egui::vec2(300.0, 400.0));
-                            ui.vertical_centered(|ui| {
-                                ui.add_space(10.0);
-                                ui.label(egui::RichText::new(&upgrade.name).size(28.0).strong());
-                                ui.add_space(10.0);
-                                ui.add(egui::Image::new(egui::load::SizedTexture::new(
-                                    upgrade.icon,
-                                    [100.0, 100.0],
-                                )));
-                                ui.add_space(10.0);
-                                ui.label(egui::RichText::new(&upgrade.description).size(16.0));
-                                ui.add_space(15.0);
+                        egui::Frame::group(ui.style())
+                            .stroke(egui::Stroke::new(3.0, rarity.color()))
+                            .show(ui, |ui| {
+                                ui.set_min_size(egui::vec2(300.0, 400.0));
+                                ui.set_max_size(egui::vec2(300.0, 400.0));
+                                ui.vertical_centered(|ui| {
+                                    ui.add_space(10.0);
+                                    ui.label(
+                                        egui::RichText::new(&upgrade.name).size(28.0).strong(),
+                                    );
+                                    ui.label(
+                                        egui::RichText::new(rarity.name())
+                                            .size(16.0)
+                                            .color(rarity.color()),
+                                    );
+                                    ui.add_space(10.0);
+                                    ui.add(egui::Image::new(egui::load::SizedTexture::new(
+                                        upgrade.icon,
+                                        [100.0, 100.0],
+                                    )));
+                                    ui.add_space(10.0);
+                                    ui.label(egui::RichText::new(&upgrade.description).size(16.0));
+                                    ui.add_space(10.0);
+                                    if upgrade.max_level > 1 {
+                                        ui.label(
+                                            egui::RichText::new(format!(
+                                                "Lv {}/{}",
+                                                upgrade.level, upgrade.max_level
+                                            ))
+                                            .size(18.0),
+                                        );
+                                    }
+                                    if let Some(before) =
+                                        upgrade_stat(&player_stats, upgrade.upgrade_id)
+                                    {
+                                        let mut preview = player_stats.clone();
+                                        apply_upgrade(&mut preview, upgrade.upgrade_id, rarity);
+                                        let after = upgrade_stat(&preview, upgrade.upgrade_id)
+                                            .unwrap_or_default();
+                                        ui.label(
+                                            egui::RichText::new(format!("{} -> {}", before, after))
+                                                .size(18.0)
+                                                .color(rarity.color()),
+                                        );
+                                    }
+                                    ui.add_space(15.0);
 
-                                if ui
-                                    .button(
-                                        egui::RichText::new("Select")
-                                            .size(20.0)
-                                            .color(egui::Color32::WHITE),
-                                    )
-                                    .clicked()
-                                {
-                                    // Apply the upgrade effects based on upgrade_id
-                                    match upgrade.upgrade_id {
-                                        0 => player_stats.num_shields += 1,
-                                        1 => player_stats.rate_of_fire *= 0.5,
-                                        2 => player_stats.projectile_speed *= 2.,
-                                        3 => player_stats.damage *= 1.25,
-                                        4 => player_stats.acceleration_rate *= 1.5,
-                                        5 => player_stats.shield_damage *= 1.25,
-                                        6 => player_stats.shield_rotation_speed += 0.02,
-                                        7 => player_stats.freeze_chance += 20,
-                                        8 => player_stats.freeze_duration += 1.,
-                                        9 => player_stats.fire_chance += 20,
-                                        10 => player_stats.fire_duration += 2.0,
-                                        11 => player_stats.fire_dps *= 1.5,
-                                        12 => player_stats.flash_freeze = true,
-                                        13 => player_stats.flash_freeze_percent_damage += 0.1,
-                                        14 => player_stats.freezer_burn = true,
-                                        15 => player_stats.freezer_burn_duration += 1.,
-                                        16 => player_stats.freezer_burn_multiplier *= 1.5,
-                                        17 => player_stats.projectile_piercing += 1,
-                                        18 => player_stats.shield_apply_effects = true,
-                                        19 => player_stats.snowball_damage_multiplier += 0.5,
-                                        20 => player_stats.knockback_strength += 1.0,
-
-                                        _ => {}
+                                    if ui
+                                        .button(
+                                            egui::RichText::new("Select")
+                                                .size(20.0)
+                                                .color(egui::Color32::WHITE),
+                                        )
+                                        .clicked()
+                                    {
+                                        apply_upgrade(
+                                            &mut player_stats,
+                                            upgrade.upgrade_id,
+                                            rarity,
+                                        );
+                                        upgrade.level += 1;
+                                        taken_upgrades.record(upgrade);
+                                        sfx.send(SfxEvent::new(Sfx::UiConfirm));
+
+                                        // Exit upgrade screen
+                                        app_state.set(GameState::Playing);
                                     }
 
-                                    // Mark the upgrade as taken and unavailable
-                                    if let Some(taken) = upgrade.taken.as_mut() {
-                                        *taken = true;
-                                        upgrade.available = false;
+                                    ui.add_space(5.0);
+                                    if ui
+                                        .add_enabled(
+                                            upgrade_charges.banishes > 0,
+                                            egui::Button::new(format!(
+                                                "Banish ({})",
+                                                upgrade_charges.banishes
+                                            )),
+                                        )
+                                        .on_hover_text(
+                                            "Remove this upgrade for the rest of the run",
+                                        )
+                                        .clicked()
+                                    {
+                                        banish_slot = Some(slot);
                                     }
+                                });
+                            });
+                    }
+                });
+            });
+        });
 
-                                    // Exit upgrade screen
-                                    app_state.set(GameState::Playing);
-                                }
+    if reroll {
+        upgrade_charges.rerolls -= 1;
+        selected_indices_res.indices =
+            upgrades.draw(3, player_stats.luck, &[], &upgrade_charges.banished);
+    } else if let Some(slot) = banish_slot {
+        upgrade_charges.banishes -= 1;
+        let (index, _) = selected_indices_res.indices.remove(slot);
+        upgrade_charges
+            .banished
+            .push(upgrades.upgrades[index].upgrade_id);
+        // Refill the slot with something not already on offer
+        let offered: Vec<usize> = selected_indices_res
+            .indices
+            .iter()
+            .map(|&(index, _)| index)
+            .collect();
+        let replacement = upgrades.draw(1, player_stats.luck, &offered, &upgrade_charges.banished);
+        if let Some(&card) = replacement.first() {
+            selected_indices_res.indices.insert(slot, card);
+        }
+    }
+}
+
+/// How many upgrades a chest can hold, and the weight of each before luck.
+const CHEST_REWARDS: [(usize, f32); 3] = [(1, 70.0), (3, 25.0), (5, 5.0)];
+/// When the first slot stops spinning, and the gap before each one after it.
+const CHEST_FIRST_REVEAL: f32 = 1.0;
+const CHEST_REVEAL_INTERVAL: f32 = 0.6;
+
+#[derive(Resource, Default)]
+struct ChestReveal {
+    rewards: Vec<(usize, Rarity)>,
+    elapsed: f32,
+    revealed: usize,
+}
+
+fn open_chest(
+    mut chest: ResMut<ChestReveal>,
+    mut upgrades: ResMut<UpgradeCards>,
+    player_stats: Res<PlayerStats>,
+    upgrade_charges: Res<UpgradeCharges>,
+) {
+    upgrades.refresh_prerequisites(&player_stats);
+    // Luck makes the bigger chests more likely, like it does the rarer tiers
+    let count = CHEST_REWARDS
+        .choose_weighted(&mut rand::thread_rng(), |&(count, weight)| {
+            weight * (1.0 + player_stats.luck * (count / 2) as f32)
+        })
+        .map_or(1, |&(count, _)| count);
+    *chest = ChestReveal {
+        rewards: upgrades.draw(count, player_stats.luck, &[], &upgrade_charges.banished),
+        elapsed: 0.0,
+        revealed: 0,
+    };
+}
+
+#[allow(clippy::too_many_arguments)]
+fn chest_screen(
+    mut egui_ctx: EguiContexts,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut chest: ResMut<ChestReveal>,
+    mut upgrades: ResMut<UpgradeCards>,
+    mut player_stats: ResMut<PlayerStats>,
+    mut taken_upgrades: ResMut<TakenUpgrades>,
+    mut run_stats: ResMut<RunStats>,
+    mut bursts: EventWriter<RevealBurst>,
+    mut sfx: EventWriter<SfxEvent>,
+    time: Res<Time>,
+) {
+    // Slots stop spinning one at a time, each with a burst in its rarity's colour
+    chest.elapsed += time.delta_secs();
+    let previously_revealed = chest.revealed;
+    while chest.revealed < chest.rewards.len()
+        && chest.elapsed >= CHEST_FIRST_REVEAL + chest.revealed as f32 * CHEST_REVEAL_INTERVAL
+    {
+        chest.revealed += 1;
+    }
+    let done = chest.revealed == chest.rewards.len();
+
+    egui::CentralPanel::default()
+        .frame(egui::Frame::none().fill(egui::Color32::from_rgba_premultiplied(0, 0, 0, 180)))
+        .show(egui_ctx.ctx_mut(), |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(40.0);
+                ui.label(egui::RichText::new("Treasure Chest!").size(48.0).strong());
+                ui.add_space(30.0);
+
+                ui.horizontal(|ui| {
+                    let slot_width = 170.0;
+                    ui.add_space(
+                        ui.available_width() / 2.0 - slot_width * chest.rewards.len() as f32 / 2.0,
+                    );
+                    for (slot, &(index, rarity)) in chest.rewards.iter().enumerate() {
+                        let revealed = slot < chest.revealed;
+                        let stroke = if revealed {
+                            rarity.color()
+                        } else {
+                            egui::Color32::GRAY
+                        };
+                        let slot_rect = egui::Frame::group(ui.style())
+                            .stroke(egui::Stroke::new(3.0, stroke))
+                            .show(ui, |ui| {
+                                ui.set_min_size(egui::vec2(150.0, 200.0));
+                                ui.set_max_size(egui::vec2(150.0, 200.0));
+                                ui.vertical_centered(|ui| {
+                                    ui.add_space(10.0);
+                                    // Spinning slots flick through the whole pool
+                                    let shown = if revealed {
+                                        &upgrades.upgrades[index]
+                                    } else {
+                                        let spin = (chest.elapsed * 12.0) as usize + slot * 7;
+                                        &upgrades.upgrades[spin % upgrades.upgrades.len()]
+                                    };
+                                    ui.add(egui::Image::new(egui::load::SizedTexture::new(
+                                        shown.icon,
+                                        [64.0, 64.0],
+                                    )));
+                                    ui.add_space(10.0);
+                                    if revealed {
+                                        ui.label(
+                                            egui::RichText::new(&shown.name).size(18.0).strong(),
+                                        );
+                                        ui.label(
+                                            egui::RichText::new(rarity.name())
+                                                .size(14.0)
+                                                .color(rarity.color()),
+                                        );
+                                    } else {
+                                        ui.label(egui::RichText::new("???").size(18.0));
+                                    }
+                                });
+                            })
+                            .response
+                            .rect;
+                        if revealed && slot >= previously_revealed {
+                            bursts.send(RevealBurst {
+                                origin: slot_rect.center(),
+                                color: rarity.color(),
                             });
-                        });
+                        }
                     }
                 });
+
+                ui.add_space(30.0);
+                if chest.rewards.is_empty() {
+                    ui.label(egui::RichText::new("The chest was empty...").size(20.0));
+                    ui.add_space(10.0);
+                }
+                if done
+                    && ui
+                        .add_sized(
+                            [220.0, 60.0],
+                            egui::Button::new(
+                                egui::RichText::new("Collect")
+                                    .size(24.0)
+                                    .color(egui::Color32::WHITE),
+                            ),
+                        )
+                        .clicked()
+                {
+                    for &(index, rarity) in &chest.rewards {
+                        let upgrade = &mut upgrades.upgrades[index];
+                        apply_upgrade(&mut player_stats, upgrade.upgrade_id, rarity);
+                        upgrade.level += 1;
+                        taken_upgrades.record(upgrade);
+                        run_stats.chest_rewards.push(upgrade.upgrade_id);
+                    }
+                    run_stats.chests_opened += 1;
+                    sfx.send(SfxEvent::new(Sfx::UiConfirm));
+                    game_state.set(GameState::Playing);
+                }
             });
         });
 }
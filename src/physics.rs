@@ -0,0 +1,135 @@
+use crate::GameState;
+use bevy::prelude::*;
+
+/// Speed given to a body of mass 1 per point of knockback strength.
+pub const KNOCKBACK_IMPULSE: f32 = 60.0;
+
+pub struct PhysicsPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for PhysicsPlugin<S> {
+    fn build(&self, app: &mut App) {
+        // Forces are gathered during FixedUpdate and integrated once per fixed step afterwards.
+        app.add_systems(
+            FixedPostUpdate,
+            integrate_bodies
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+/// A body moved by velocity instead of writing to its `Transform` directly.
+#[derive(Component, Clone, Debug)]
+pub struct KinematicBody {
+    pub velocity: Vec2,
+    pub mass: f32,
+    /// Exponential decay rate of velocity, so slowing down is frame-rate independent.
+    pub drag: f32,
+    /// Top speed forces can push the body to. Impulses may exceed it and are slowed by drag.
+    pub max_speed: Option<f32>,
+    force: Vec2,
+}
+
+impl KinematicBody {
+    pub fn new(mass: f32, drag: f32) -> Self {
+        Self {
+            velocity: Vec2::ZERO,
+            mass,
+            drag,
+            max_speed: None,
+            force: Vec2::ZERO,
+        }
+    }
+    pub fn with_velocity(mut self, velocity: Vec2) -> Self {
+        self.velocity = velocity;
+        self
+    }
+    pub fn with_max_speed(mut self, max_speed: f32) -> Self {
+        self.max_speed = Some(max_speed);
+        self
+    }
+
+    /// Instant change in momentum, e.g. knockback.
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        self.velocity += impulse / self.mass;
+    }
+    /// Continuous push, accumulated until the next integration step.
+    pub fn add_force(&mut self, force: Vec2) {
+        self.force += force;
+    }
+
+    /// Advances the body by `dt` seconds and returns how far it moved.
+    pub fn step(&mut self, dt: f32) -> Vec2 {
+        let speed_before = self.velocity.length();
+        self.velocity += self.force / self.mass * dt;
+        self.force = Vec2::ZERO;
+        if let Some(max_speed) = self.max_speed {
+            self.velocity = self.velocity.clamp_length_max(max_speed.max(speed_before));
+        }
+        self.velocity *= (-self.drag * dt).exp();
+        self.velocity * dt
+    }
+}
+
+fn integrate_bodies(mut q: Query<(&mut Transform, &mut KinematicBody)>, time: Res<Time>) {
+    let dt = time.delta_secs();
+    for (mut transform, mut body) in q.iter_mut() {
+        transform.translation += body.step(dt).extend(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn drag_is_independent_of_step_size() {
+        let mut whole = KinematicBody::new(1.0, 3.0).with_velocity(Vec2::new(120.0, -40.0));
+        let mut split = whole.clone();
+
+        whole.step(DT);
+        for _ in 0..8 {
+            split.step(DT / 8.0);
+        }
+
+        assert!(whole.velocity.distance(split.velocity) < 1e-3);
+    }
+
+    #[test]
+    fn impulse_scales_with_inverse_mass() {
+        let mut light = KinematicBody::new(1.0, 0.0);
+        let mut heavy = KinematicBody::new(4.0, 0.0);
+
+        light.apply_impulse(Vec2::new(8.0, 0.0));
+        heavy.apply_impulse(Vec2::new(8.0, 0.0));
+
+        assert_eq!(light.velocity, Vec2::new(8.0, 0.0));
+        assert_eq!(heavy.velocity, Vec2::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn max_speed_caps_forces() {
+        let mut body = KinematicBody::new(1.0, 0.0).with_max_speed(10.0);
+
+        body.add_force(Vec2::new(10_000.0, 0.0));
+        body.step(DT);
+
+        assert!((body.velocity.length() - 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn max_speed_leaves_impulses_alone() {
+        let mut body = KinematicBody::new(1.0, 0.0).with_max_speed(10.0);
+
+        body.apply_impulse(Vec2::new(50.0, 0.0));
+        body.add_force(Vec2::new(10_000.0, 0.0));
+        body.step(DT);
+
+        // Not clamped back to max_speed, but forces can't push it any faster either
+        assert!((body.velocity.length() - 50.0).abs() < 1e-4);
+    }
+}
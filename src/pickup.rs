@@ -1,8 +1,31 @@
-use crate::player::Player;
+use crate::audio::{Sfx, SfxEvent};
+use crate::camera::{InGameCamera, VIEW_RADIUS};
+use crate::collision::Blink;
+use crate::damage_numbers::{DamageEvent, DamageKind};
+use crate::enemy::{Enemy, EnemyHealth, EnemyXp};
+use crate::juice::Impact;
+use crate::mainmenu::UpgradeCharges;
+use crate::physics::KinematicBody;
+use crate::player::{Player, PlayerHealth, PlayerStats, PoweredUp, PLAYER_START_HP};
+use crate::status::{ApplyStatus, StatusKind, StatusSource};
 use crate::utils::YSort;
 use crate::GameState;
 use bevy::prelude::*;
+use rand::seq::SliceRandom;
 use rand::Rng;
+
+const PICKUP_HEAL: f32 = 3.0;
+/// Speed XP and pickups drift towards the player at.
+const MAGNETIC_SPEED: f32 = 200.0;
+/// How quickly orbs vacuumed up by a magnet speed up, per second.
+const MAGNET_ACCELERATION: f32 = 600.0;
+/// Drag on XP and pickup bodies; high enough that they turn sharply onto the player.
+pub const MAGNETIC_DRAG: f32 = 8.0;
+const XP_BOOST_MULTIPLIER: f32 = 2.0;
+const XP_BOOST_DURATION: f32 = 10.0;
+/// Chance any pickup also hands out an upgrade reroll.
+const PICKUP_REROLL_CHANCE: f64 = 0.1;
+
 pub struct PickupPlugin<S: States> {
     pub state: S,
 }
@@ -10,27 +33,225 @@ pub struct PickupPlugin<S: States> {
 impl<S: States> Plugin for PickupPlugin<S> {
     fn build(&self, app: &mut App) {
         app.insert_resource(PickupTimer(Timer::from_seconds(1.0, TimerMode::Repeating)));
+        app.insert_resource(XpBoost::default());
+        app.insert_resource(PendingChests::default());
+        app.add_event::<DropPickup>();
+        app.add_event::<PickupCollected>();
         app.add_systems(
             Update,
-            (spawn_pickup, pickup_hover)
+            (
+                spawn_pickup,
+                spawn_drops,
+                collect_pickups,
+                open_pending_chest,
+                tick_xp_boost,
+                pickup_hover,
+            )
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Playing)),
+        );
+        app.add_systems(
+            FixedUpdate,
+            attract_to_player
                 .run_if(in_state(self.state.clone()))
                 .run_if(in_state(GameState::Playing)),
         );
+        app.add_systems(OnEnter(GameState::Chest), spend_pending_chest);
         app.add_systems(OnExit(self.state.clone()), clean_up_pickups);
     }
 }
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PickupKind {
+    SugarRush,
+    Heal,
+    /// Pulls every XP orb on the map to the player
+    Magnet,
+    /// Kills everything on screen
+    Bomb,
+    FreezeAll,
+    XpMultiplier,
+    /// Opens into one, three or five free upgrades
+    Chest,
+}
+
+struct PickupDef {
+    sprite: &'static str,
+    tint: Color,
+    scale: f32,
+}
+
+impl PickupKind {
+    fn def(self) -> PickupDef {
+        match self {
+            PickupKind::SugarRush => PickupDef {
+                sprite: "Candycane.png",
+                tint: Color::WHITE,
+                scale: 1.0,
+            },
+            PickupKind::Heal => PickupDef {
+                sprite: "Candycane.png",
+                tint: Color::srgb(0.4, 1.0, 0.4),
+                scale: 1.0,
+            },
+            PickupKind::Magnet => PickupDef {
+                sprite: "xp.png",
+                tint: Color::srgb(0.6, 0.8, 1.0),
+                scale: 1.5,
+            },
+            PickupKind::Bomb => PickupDef {
+                sprite: "fire_icon.png",
+                tint: Color::WHITE,
+                scale: 0.5,
+            },
+            PickupKind::FreezeAll => PickupDef {
+                sprite: "freeze.png",
+                tint: Color::WHITE,
+                scale: 0.5,
+            },
+            PickupKind::XpMultiplier => PickupDef {
+                sprite: "xp.png",
+                tint: Color::srgb(1.0, 0.85, 0.2),
+                scale: 1.5,
+            },
+            PickupKind::Chest => PickupDef {
+                sprite: "chestnut.png",
+                tint: Color::srgb(1.0, 0.8, 0.3),
+                scale: 1.5,
+            },
+        }
+    }
+}
+
+/// Where a drop comes from; each source rolls on its own table.
+#[derive(Clone, Copy)]
+pub enum DropSource {
+    Timer,
+    EnemyDeath,
+    /// Elites always drop something good
+    Elite,
+}
+
+impl DropSource {
+    /// Chance anything drops at all
+    fn chance(self) -> f64 {
+        match self {
+            DropSource::Timer | DropSource::Elite => 1.0,
+            DropSource::EnemyDeath => 0.02,
+        }
+    }
+
+    fn table(self) -> &'static [(PickupKind, f32)] {
+        match self {
+            DropSource::Timer => &[
+                (PickupKind::SugarRush, 70.0),
+                (PickupKind::Heal, 12.0),
+                (PickupKind::Magnet, 6.0),
+                (PickupKind::XpMultiplier, 6.0),
+                (PickupKind::FreezeAll, 3.0),
+                (PickupKind::Bomb, 3.0),
+            ],
+            DropSource::EnemyDeath => &[
+                (PickupKind::Heal, 40.0),
+                (PickupKind::Magnet, 25.0),
+                (PickupKind::SugarRush, 20.0),
+                (PickupKind::XpMultiplier, 10.0),
+                (PickupKind::Bomb, 5.0),
+            ],
+            DropSource::Elite => &[
+                (PickupKind::Chest, 70.0),
+                (PickupKind::Bomb, 15.0),
+                (PickupKind::FreezeAll, 15.0),
+            ],
+        }
+    }
+
+    fn roll(self, rng: &mut impl Rng) -> Option<PickupKind> {
+        if !rng.gen_bool(self.chance()) {
+            return None;
+        }
+        self.table()
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .ok()
+            .map(|(kind, _)| *kind)
+    }
+}
+
+/// Asks for a roll on a source's drop table at a position.
+#[derive(Event)]
+pub struct DropPickup {
+    pub position: Vec2,
+    pub source: DropSource,
+}
+
+/// Sent when the player touches a pickup.
+#[derive(Event)]
+pub struct PickupCollected {
+    pub kind: PickupKind,
+}
+
 #[derive(Component)]
-pub struct Pickup;
+pub struct Pickup {
+    pub kind: PickupKind,
+}
 #[derive(Component)]
 struct PickupShadow;
+
+/// Drifts towards the player once within their pickup radius, or from anywhere once vacuumed.
+#[derive(Component)]
+pub struct Magnetic {
+    speed: f32,
+    pulled: bool,
+    /// Pulled in by a magnet pickup, speeding up the whole way
+    vacuumed: bool,
+}
+
+impl Default for Magnetic {
+    fn default() -> Self {
+        Self {
+            speed: MAGNETIC_SPEED,
+            pulled: false,
+            vacuumed: false,
+        }
+    }
+}
 #[derive(Resource)]
 struct PickupTimer(Timer);
+
+/// Multiplies collected XP while the timer runs.
+#[derive(Resource)]
+pub struct XpBoost {
+    timer: Timer,
+}
+
+impl Default for XpBoost {
+    fn default() -> Self {
+        let mut timer = Timer::from_seconds(XP_BOOST_DURATION, TimerMode::Once);
+        timer.tick(timer.duration());
+        Self { timer }
+    }
+}
+
+/// Chests picked up but not opened yet, so two chests or a chest and a level-up landing
+/// together each still get their own screen.
+#[derive(Resource, Default)]
+struct PendingChests(u32);
+
+impl XpBoost {
+    pub fn multiplier(&self) -> f32 {
+        if self.timer.finished() {
+            1.0
+        } else {
+            XP_BOOST_MULTIPLIER
+        }
+    }
+}
+
 fn spawn_pickup(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
     player: Query<&Transform, With<Player>>,
     time: Res<Time>,
     mut pickup_timer: ResMut<PickupTimer>,
+    mut drops: EventWriter<DropPickup>,
 ) {
     let Ok(player_transform) = player.get_single() else {
         return;
@@ -42,13 +263,36 @@ fn spawn_pickup(
         let distance = rng.gen_range(100.0..200.0);
 
         let offset = Vec2::new(angle.cos() * distance, angle.sin() * distance);
-        let pickup_pos = player_transform.translation.truncate() + offset;
+        drops.send(DropPickup {
+            position: player_transform.translation.truncate() + offset,
+            source: DropSource::Timer,
+        });
+    }
+}
 
+fn spawn_drops(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut drops: EventReader<DropPickup>,
+) {
+    let mut rng = rand::thread_rng();
+    for drop in drops.read() {
+        let Some(kind) = drop.source.roll(&mut rng) else {
+            continue;
+        };
+        let def = kind.def();
         let pickup_entity = commands
             .spawn((
-                Sprite::from_image(asset_server.load("candycane.png")),
-                Transform::from_translation(pickup_pos.extend(1.0)),
-                Pickup,
+                Sprite {
+                    image: asset_server.load(def.sprite),
+                    color: def.tint,
+                    ..default()
+                },
+                Transform::from_translation(drop.position.extend(1.0))
+                    .with_scale(Vec3::splat(def.scale)),
+                Pickup { kind },
+                Magnetic::default(),
+                KinematicBody::new(1.0, MAGNETIC_DRAG),
             ))
             .id();
         let shadow_entity = commands
@@ -57,16 +301,145 @@ fn spawn_pickup(
                 Transform {
                     translation: Vec3::new(-1.0, -7.0, 0.0),
                     rotation: Quat::from_rotation_z(0.0),
-                    scale: Vec3::new(1.5, 1.5, 1.0),
+                    scale: Vec3::new(1.5, 1.5, 1.0) / def.scale,
                 },
                 PickupShadow,
                 YSort { z: -100.0 },
             ))
             .id();
         commands.entity(pickup_entity).add_child(shadow_entity);
-        println!("Spawned pickup");
     }
 }
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn collect_pickups(
+    mut commands: Commands,
+    mut sfx: EventWriter<SfxEvent>,
+    mut collected: EventReader<PickupCollected>,
+    mut q_player: Query<(Entity, &Transform, &mut PlayerHealth), With<Player>>,
+    mut q_enemy: Query<(Entity, &Transform, &mut EnemyHealth), (With<Enemy>, Without<Player>)>,
+    mut q_xp: Query<&mut Magnetic, With<EnemyXp>>,
+    q_camera: Query<&OrthographicProjection, With<InGameCamera>>,
+    mut apply_status: EventWriter<ApplyStatus>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut pending_chests: ResMut<PendingChests>,
+    mut xp_boost: ResMut<XpBoost>,
+    mut upgrade_charges: ResMut<UpgradeCharges>,
+    mut impacts: EventWriter<Impact>,
+) {
+    let Ok((player_entity, player_tf, mut player_health)) = q_player.get_single_mut() else {
+        return;
+    };
+    let player_pos = player_tf.translation.truncate();
+    // Bombs and freeze-alls hit everything out to roughly the edge of the screen
+    let screen_radius = VIEW_RADIUS * q_camera.get_single().map_or(1.0, |p| p.scale);
+    for pickup in collected.read() {
+        if rand::thread_rng().gen_bool(PICKUP_REROLL_CHANCE) {
+            upgrade_charges.rerolls += 1;
+        }
+        match pickup.kind {
+            PickupKind::SugarRush => {
+                commands.entity(player_entity).insert((
+                    PoweredUp {
+                        timer: Timer::from_seconds(5.0, TimerMode::Once),
+                    },
+                    Blink {
+                        color: Color::srgba(0., 1., 0., 1.),
+                        speed: 1.0,
+                    },
+                ));
+            }
+            PickupKind::Heal => {
+                // Never heals past full, but doesn't take away overheal from kills either
+                let max_hp = PLAYER_START_HP.max(player_health.hp);
+                player_health.hp = (player_health.hp + PICKUP_HEAL).min(max_hp);
+            }
+            PickupKind::Magnet => {
+                for mut magnetic in q_xp.iter_mut() {
+                    magnetic.pulled = true;
+                    magnetic.vacuumed = true;
+                }
+            }
+            PickupKind::Bomb => {
+                impacts.send(Impact::BOMB);
+                for (enemy_entity, enemy_tf, mut enemy_health) in q_enemy.iter_mut() {
+                    let enemy_pos = enemy_tf.translation.truncate();
+                    if enemy_pos.distance(player_pos) > screen_radius || enemy_health.health <= 0.0
+                    {
+                        continue;
+                    }
+                    damage_events.send(DamageEvent {
+                        target: enemy_entity,
+                        position: enemy_pos,
+                        amount: enemy_health.health,
+                        kind: DamageKind::Fire,
+                        over_time: false,
+                    });
+                    enemy_health.health = 0.0;
+                }
+            }
+            PickupKind::FreezeAll => {
+                for (enemy_entity, enemy_tf, _) in q_enemy.iter() {
+                    if enemy_tf.translation.truncate().distance(player_pos) <= screen_radius {
+                        apply_status.send(ApplyStatus {
+                            target: enemy_entity,
+                            kind: StatusKind::Frozen,
+                            source: StatusSource::Pickup,
+                        });
+                    }
+                }
+            }
+            PickupKind::XpMultiplier => xp_boost.timer.reset(),
+            PickupKind::Chest => pending_chests.0 += 1,
+        }
+        sfx.send(SfxEvent::new(Sfx::Pickup));
+    }
+}
+
+fn attract_to_player(
+    q_player: Query<&Transform, With<Player>>,
+    mut q_magnetic: Query<(&Transform, &mut KinematicBody, &mut Magnetic), Without<Player>>,
+    stats: Res<PlayerStats>,
+    time: Res<Time>,
+) {
+    let Ok(player_tf) = q_player.get_single() else {
+        return;
+    };
+    let dt = time.delta_secs();
+    for (transform, mut body, mut magnetic) in q_magnetic.iter_mut() {
+        let offset = player_tf.translation.truncate() - transform.translation.truncate();
+        if !magnetic.pulled && offset.length() > stats.pickup_radius {
+            continue;
+        }
+        magnetic.pulled = true;
+        if magnetic.vacuumed {
+            magnetic.speed += MAGNET_ACCELERATION * dt;
+        }
+        // Against the body's drag this settles at `magnetic.speed`
+        let force = offset.normalize_or_zero() * magnetic.speed * body.drag * body.mass;
+        body.add_force(force);
+    }
+}
+
+/// Opens the next chest once no other screen has been requested this frame.
+fn open_pending_chest(
+    pending_chests: Res<PendingChests>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    if pending_chests.0 > 0 && matches!(*game_state, NextState::Unchanged) {
+        game_state.set(GameState::Chest);
+    }
+}
+
+/// A chest is only used up once its screen actually opens.
+fn spend_pending_chest(mut pending_chests: ResMut<PendingChests>) {
+    pending_chests.0 = pending_chests.0.saturating_sub(1);
+}
+
+fn tick_xp_boost(mut xp_boost: ResMut<XpBoost>, time: Res<Time>) {
+    xp_boost.timer.tick(time.delta());
+}
+
 fn pickup_hover(
     mut q_pickup: Query<&mut Transform, (With<Pickup>, Without<PickupShadow>)>,
     mut q_shadow: Query<&mut Transform, With<PickupShadow>>,
@@ -80,7 +453,14 @@ fn pickup_hover(
         transform.translation.y += (time * 4.0).sin() * 0.1;
     }
 }
-fn clean_up_pickups(mut commands: Commands, pickup_query: Query<Entity, With<Pickup>>) {
+fn clean_up_pickups(
+    mut commands: Commands,
+    pickup_query: Query<Entity, With<Pickup>>,
+    mut xp_boost: ResMut<XpBoost>,
+    mut pending_chests: ResMut<PendingChests>,
+) {
+    *xp_boost = XpBoost::default();
+    *pending_chests = PendingChests::default();
     for pickup in pickup_query.iter() {
         commands.entity(pickup).despawn_recursive();
     }
@@ -1,5 +1,12 @@
-use crate::camera::{InGameCamera, OuterCamera, Resolution, Rotate};
+use crate::camera::{CameraZoom, InGameCamera, OuterCamera, Rotate};
+use crate::character::{RunStats, SelectedCharacter};
 use crate::collision::Blink;
+use crate::dash::{DashCharges, Dashing, DASH_DRAG};
+use crate::enemy::Enemy;
+use crate::evolution::Evolution;
+use crate::juice::ScreenShake;
+use crate::physics::KinematicBody;
+use crate::snowball::{SnowballMass, BASE_MASS};
 use crate::utils::YSort;
 use crate::AppState;
 use crate::GameState;
@@ -7,12 +14,11 @@ use bevy::input::keyboard::KeyCode;
 use bevy::input::mouse::MouseButton;
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
-use bevy_egui::egui::epaint::stats;
 use bevy_hanabi::prelude::*;
 
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct PlayerStats {
-    pub xp_requirement: f32,
+    pub shurikens: bool,
     pub damage: f32,
     pub rate_of_fire: f32,
     pub acceleration_rate: f32,
@@ -24,7 +30,6 @@ pub struct PlayerStats {
 
     pub projectile_speed: f32,
     pub projectile_piercing: i32,
-    pub projectile_bounces: i32,
 
     pub freeze_chance: i32,
     pub freeze_duration: f32,
@@ -40,25 +45,55 @@ pub struct PlayerStats {
     pub freezer_burn_duration: f32,
     pub freezer_burn_multiplier: f32,
 
+    pub chill_chance: i32,
+    pub chill_duration: f32,
+    pub chill_slow_per_stack: f32,
+    pub chill_stacks_to_freeze: u32,
+
+    pub shock_chance: i32,
+    pub shock_damage: f32,
+    pub shock_chains: usize,
+    pub shock_range: f32,
+
+    pub poison_chance: i32,
+    pub poison_duration: f32,
+    pub poison_dps: f32, // Per stack
+    pub poison_max_stacks: u32,
+    pub poison_spread_radius: f32,
+
+    pub superconduct: bool, // Shocking chilled enemies deals extra damage
+    pub superconduct_multiplier: f32,
+
+    pub toxic_flare: bool, // Burning poisoned enemies detonates the poison
+    pub toxic_flare_damage: f32, // Per poison stack
+
     pub snowball_damage_multiplier: f32,
     pub knockback_strength: f32,
-}
 
-pub struct PlayerPlugin<S: States> {
-    pub state: S,
+    pub crit_chance: i32,
+    pub crit_multiplier: f32,
+
+    pub luck: f32,          // Shifts upgrade draws towards rarer tiers
+    pub pickup_radius: f32, // XP and pickups start drifting in from this far away
+
+    pub evolutions: Vec<Evolution>,
+
+    pub dash_charges: u32,
+    pub dash_distance: f32,
+    pub dash_cooldown: f32,
+    pub dash_freeze_trail: bool, // Dashing leaves a trail that freezes enemies
 }
 
-impl<S: States> Plugin for PlayerPlugin<S> {
-    fn build(&self, app: &mut App) {
-        app.insert_resource(PlayerStats {
-            xp_requirement: 10.0,
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self {
+            shurikens: true,
             rate_of_fire: 0.4,
             acceleration_rate: 500.0,
 
             damage: 34.,
             projectile_speed: 550.0,
             projectile_piercing: 0,
-            projectile_bounces: 0,
 
             num_shields: 0,
             shield_damage: 15.0,
@@ -79,21 +114,75 @@ impl<S: States> Plugin for PlayerPlugin<S> {
             freezer_burn_duration: 2.,
             freezer_burn_multiplier: 2.,
 
+            chill_chance: 0,
+            chill_duration: 3.,
+            chill_slow_per_stack: 0.15,
+            chill_stacks_to_freeze: 5,
+
+            shock_chance: 0,
+            shock_damage: 10.,
+            shock_chains: 2,
+            shock_range: 60.,
+
+            poison_chance: 0,
+            poison_duration: 4.,
+            poison_dps: 4.,
+            poison_max_stacks: 5,
+            poison_spread_radius: 40.,
+
+            superconduct: false,
+            superconduct_multiplier: 2.,
+
+            toxic_flare: false,
+            toxic_flare_damage: 15.,
+
             snowball_damage_multiplier: 1.0,
             knockback_strength: 3.,
-        });
+
+            crit_chance: 5,
+            crit_multiplier: 2.,
+
+            luck: 0.,
+            pickup_radius: 80.,
+
+            evolutions: Vec::new(),
+
+            dash_charges: 1,
+            dash_distance: 60.,
+            dash_cooldown: 2.,
+            dash_freeze_trail: false,
+        }
+    }
+}
+
+impl PlayerStats {
+    pub fn has_evolution(&self, evolution: Evolution) -> bool {
+        self.evolutions.contains(&evolution)
+    }
+}
+
+pub struct PlayerPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for PlayerPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PlayerStats::default());
         app.insert_resource(LastShieldCount::default());
-        app.add_systems(OnEnter(self.state.clone()), spawn_player);
+        app.add_systems(
+            OnEnter(self.state.clone()),
+            (reset_player_stats, spawn_player).chain(),
+        );
         app.add_systems(
             FixedUpdate,
             (
                 player_movement,
                 fire_projectile,
                 animate_sprite,
-                scale_snowball_to_health,
                 kill_player,
                 shield_movement,
                 camera_follow,
+                update_camera_zoom,
                 upgrade_player,
                 delay_fire,
                 update_shields,
@@ -103,24 +192,35 @@ impl<S: States> Plugin for PlayerPlugin<S> {
         );
         app.add_systems(
             Update,
-            (projectile_movement, powerup_player)
+            powerup_player
                 .run_if(in_state(self.state.clone()))
                 .run_if(in_state(GameState::Playing)),
         );
+        app.add_systems(OnEnter(GameState::Upgrade), spend_pending_level);
     }
 }
 const LERP_FACTOR: f32 = 4.0;
+/// Seconds of movement the camera leads the player by
+const LOOK_AHEAD_TIME: f32 = 0.35;
+const MAX_MOVE_LOOK_AHEAD: f32 = 48.0;
+/// Fraction of the way to the cursor the camera leans
+const AIM_LOOK_AHEAD: f32 = 0.15;
+const MAX_AIM_LOOK_AHEAD: f32 = 32.0;
+/// Enemies this close count toward zooming out, up to `CROWD_FULL_ZOOM` of them
+const CROWD_RADIUS: f32 = 250.0;
+const CROWD_FULL_ZOOM: f32 = 80.0;
+/// Extra zoom a full crowd adds on top of the snowball's size
+const CROWD_ZOOM: f32 = 0.5;
+pub const PLAYER_START_HP: f32 = 10.0;
+/// Drag while a movement key is held, low enough that the snowball keeps its momentum.
+const PLAYER_ROLLING_DRAG: f32 = 0.3;
+/// Drag once all movement keys are released, so the snowball rolls to a stop.
+const PLAYER_BRAKING_DRAG: f32 = 2.0;
 
 #[derive(Component)]
-pub struct Player {
-    pub velocity: Vec2,
-    max_velocity: f32,
-}
+pub struct Player;
 #[derive(Component)]
 pub struct Projectile {
-    velocity: f32,
-    direction: Vec2,
-    pub damage: f32,
     pub pierce_amount: i32,
 }
 #[derive(Component)]
@@ -128,12 +228,77 @@ struct ShieldCircle {
     number: u32,
 }
 #[derive(Component)]
-pub struct Shield {
-    pub damage: f32,
-}
+pub struct Shield;
 #[derive(Component)]
 pub struct PlayerXp {
     pub xp: f32,
+    pub level: u32,
+    /// Level-ups still waiting for their upgrade screen
+    pub pending_levels: u32,
+}
+
+impl Default for PlayerXp {
+    fn default() -> Self {
+        Self {
+            xp: 0.0,
+            level: 1,
+            pending_levels: 0,
+        }
+    }
+}
+
+impl PlayerXp {
+    /// XP needed to go from the current level to the next.
+    pub fn requirement(&self) -> f32 {
+        xp_requirement(self.level)
+    }
+}
+
+enum Growth {
+    /// Flat XP added per level
+    Linear(f32),
+    /// Requirement multiplied by this per level
+    Exponential(f32),
+}
+
+/// One stretch of the level curve, in effect from `from_level` until the next segment starts.
+struct CurveSegment {
+    from_level: u32,
+    growth: Growth,
+}
+
+const BASE_XP_REQUIREMENT: f32 = 10.0;
+const LEVEL_CURVE: [CurveSegment; 3] = [
+    CurveSegment {
+        from_level: 1,
+        growth: Growth::Linear(4.0),
+    },
+    CurveSegment {
+        from_level: 20,
+        growth: Growth::Linear(10.0),
+    },
+    CurveSegment {
+        from_level: 40,
+        growth: Growth::Exponential(1.08),
+    },
+];
+
+fn xp_requirement(level: u32) -> f32 {
+    let mut requirement = BASE_XP_REQUIREMENT;
+    for from in 1..level {
+        let Some(segment) = LEVEL_CURVE
+            .iter()
+            .rev()
+            .find(|segment| segment.from_level <= from)
+        else {
+            continue;
+        };
+        match segment.growth {
+            Growth::Linear(step) => requirement += step,
+            Growth::Exponential(factor) => requirement *= factor,
+        }
+    }
+    requirement
 }
 #[derive(Component)]
 pub struct PlayerHealth {
@@ -150,37 +315,58 @@ pub struct FireTimer {
     pub timer: Timer,
 }
 
+/// Every run starts from the default stats with the selected character's kit on top.
+fn reset_player_stats(
+    mut player_stats: ResMut<PlayerStats>,
+    mut last_shield_count: ResMut<LastShieldCount>,
+    selected_character: Res<SelectedCharacter>,
+) {
+    *player_stats = PlayerStats::default();
+    selected_character.def().apply(&mut player_stats);
+    *last_shield_count = LastShieldCount::default();
+}
+
 fn spawn_player(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     mut effects: ResMut<Assets<EffectAsset>>,
     player_stats: Res<PlayerStats>,
+    selected_character: Res<SelectedCharacter>,
 ) {
-    let texture = asset_server.load("elf.png");
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(48), 4, 1, None, None);
+    let character = selected_character.def();
+    let texture = asset_server.load(character.sprite);
+    let layout = TextureAtlasLayout::from_grid(UVec2::splat(48), character.columns, 1, None, None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
     // Use only the subset of sprites in the sheet that make up the run animation
-    let animation_indices = AnimationIndices { first: 0, last: 2 };
+    let animation_indices = AnimationIndices {
+        first: 0,
+        last: character.frames as usize - 1,
+    };
+    let player_sprite = Sprite::from_atlas_image(
+        texture,
+        TextureAtlas {
+            layout: texture_atlas_layout,
+            index: animation_indices.first,
+        },
+    );
     let player = commands
         .spawn((
-            Player {
-                velocity: Vec2::ZERO,
-                max_velocity: 100.0,
-            },
-            Sprite::from_atlas_image(
-                texture,
-                TextureAtlas {
-                    layout: texture_atlas_layout,
-                    index: animation_indices.first,
-                },
-            ),
+            Player,
+            KinematicBody::new(BASE_MASS, PLAYER_ROLLING_DRAG).with_max_speed(100.0),
+            player_sprite,
             animation_indices,
             AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
             Transform::from_xyz(0.0, 0.0, 0.0),
             YSort { z: 32.0 },
-            PlayerXp { xp: 0.0 },
-            PlayerHealth { hp: 10.0 },
+            PlayerXp::default(),
+            PlayerHealth {
+                hp: PLAYER_START_HP,
+            },
+            SnowballMass {
+                mass: BASE_MASS + character.bonus_mass(),
+            },
+            DashCharges::new(&player_stats),
             ShieldCircle {
                 number: player_stats.num_shields,
             },
@@ -293,12 +479,11 @@ fn spawn_player(
 }
 
 fn player_movement(
-    time: Res<Time>,
-    mut query: Query<(&mut Transform, &mut Player)>,
+    mut query: Query<(&mut KinematicBody, &SnowballMass, Option<&Dashing>), With<Player>>,
     stats: Res<PlayerStats>,
     keys: Res<ButtonInput<KeyCode>>,
 ) {
-    let Ok((mut transform, mut player)) = query.get_single_mut() else {
+    let Ok((mut body, snowball, dashing)) = query.get_single_mut() else {
         return;
     };
 
@@ -317,80 +502,132 @@ fn player_movement(
         acceleration_vector.x += stats.acceleration_rate;
     }
 
-    // Apply acceleration to velocity
-    player.velocity += acceleration_vector * time.delta_secs();
+    body.drag = if dashing.is_some() {
+        DASH_DRAG
+    } else if acceleration_vector == Vec2::ZERO {
+        PLAYER_BRAKING_DRAG
+    } else {
+        PLAYER_ROLLING_DRAG
+    };
 
-    // Clamp speed to max_velocity
-    let current_speed = player.velocity.length();
-    if current_speed > player.max_velocity {
-        player.velocity = player.velocity.normalize() * player.max_velocity;
+    // A heavier snowball keeps rolling forward but resists changing direction
+    let inertia = (snowball.mass / BASE_MASS).max(1.0);
+    if let Some(heading) = body.velocity.try_normalize() {
+        let forward = heading * acceleration_vector.dot(heading).max(0.0);
+        acceleration_vector = forward + (acceleration_vector - forward) / inertia;
     }
 
-    // Move player based on velocity
-    transform.translation += player.velocity.extend(0.0) * time.delta_secs();
+    let mass = body.mass;
+    body.add_force(acceleration_vector * mass);
 }
 
+/// Where the cursor is in the world, going through the canvas and the zoomed in-game camera.
+fn cursor_world_position(
+    window: &Window,
+    outer_camera: (&Camera, &GlobalTransform),
+    in_camera: (&GlobalTransform, &OrthographicProjection),
+) -> Option<Vec2> {
+    let (camera, camera_transform) = outer_camera;
+    let (in_transform, in_projection) = in_camera;
+    let canvas_position = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())?;
+    Some(canvas_position * in_projection.scale + in_transform.translation().truncate())
+}
+
+#[allow(clippy::type_complexity)]
 fn camera_follow(
-    mut camera_query: Query<&mut Transform, (With<InGameCamera>, Without<Player>)>,
-    player_query: Query<&Transform, With<Player>>,
+    mut camera_query: Query<
+        (&mut Transform, &GlobalTransform, &OrthographicProjection),
+        (With<InGameCamera>, Without<Player>),
+    >,
+    player_query: Query<(&Transform, &KinematicBody), With<Player>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_outer_camera: Query<(&Camera, &GlobalTransform), With<OuterCamera>>,
+    shake: Res<ScreenShake>,
+    mut applied_shake: Local<Vec2>,
     time: Res<Time>,
 ) {
-    let Ok(mut camera_transform) = camera_query.get_single_mut() else {
+    let Ok((mut camera_transform, camera_global, projection)) = camera_query.get_single_mut()
+    else {
         return;
     };
-    let Ok(player_transform) = player_query.get_single() else {
+    let Ok((player_transform, body)) = player_query.get_single() else {
         return;
     };
+    let player_position = player_transform.translation.truncate();
+
+    // Lead the player in the direction they're rolling and leaning toward the cursor
+    let mut look_ahead = (body.velocity * LOOK_AHEAD_TIME).clamp_length_max(MAX_MOVE_LOOK_AHEAD);
+    if let (Ok(window), Ok(outer_camera)) = (q_window.get_single(), q_outer_camera.get_single()) {
+        if let Some(cursor) =
+            cursor_world_position(window, outer_camera, (camera_global, projection))
+        {
+            look_ahead +=
+                ((cursor - player_position) * AIM_LOOK_AHEAD).clamp_length_max(MAX_AIM_LOOK_AHEAD);
+        }
+    }
 
-    let target = player_transform.translation;
-    let current = camera_transform.translation;
+    // Follow from where the camera would be without last frame's shake
+    let target = (player_position + look_ahead).extend(player_transform.translation.z);
+    let current = camera_transform.translation - applied_shake.extend(0.0);
     let lerp_factor = LERP_FACTOR * time.delta_secs();
 
-    camera_transform.translation = current.lerp(target, lerp_factor);
+    camera_transform.translation = current.lerp(target, lerp_factor) + shake.offset.extend(0.0);
+    *applied_shake = shake.offset;
 }
 
-fn projectile_movement(time: Res<Time>, mut query: Query<(&mut Transform, &Projectile)>) {
-    for (mut transform, projectile) in query.iter_mut() {
-        transform.translation +=
-            (projectile.direction * projectile.velocity * time.delta_secs()).extend(0.0);
-    }
+/// Bigger snowballs and thicker crowds pull the camera out.
+fn update_camera_zoom(
+    q_player: Query<(&Transform, &SnowballMass), With<Player>>,
+    q_enemy: Query<&Transform, With<Enemy>>,
+    mut zoom: ResMut<CameraZoom>,
+) {
+    let Ok((player_transform, snowball)) = q_player.get_single() else {
+        return;
+    };
+    let player_position = player_transform.translation.truncate();
+    let crowd = q_enemy
+        .iter()
+        .filter(|tf| tf.translation.truncate().distance(player_position) < CROWD_RADIUS)
+        .count();
+    let size_zoom = snowball.scale() / 1.5;
+    let crowd_zoom = (crowd as f32 / CROWD_FULL_ZOOM).min(1.0) * CROWD_ZOOM;
+    zoom.target = size_zoom + crowd_zoom;
 }
+
+#[allow(clippy::too_many_arguments)]
 fn fire_projectile(
     q_window: Query<&Window, With<PrimaryWindow>>,
     q_camera: Query<(&Camera, &GlobalTransform), With<OuterCamera>>,
-    q_incamera: Query<&GlobalTransform, With<InGameCamera>>,
+    q_incamera: Query<(&GlobalTransform, &OrthographicProjection), With<InGameCamera>>,
     q_player: Query<(&Transform, Entity, Option<&FireTimer>), With<Player>>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mouse_button: Res<ButtonInput<MouseButton>>,
     stats: Res<PlayerStats>,
 ) {
-    let (camera, camera_transform) = q_camera.single();
-    let camera_in_transform = q_incamera.single();
     let window = q_window.single();
-    if let Some(world_position) = window
-        .cursor_position()
-        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+    if let Some(new_world_position) =
+        cursor_world_position(window, q_camera.single(), q_incamera.single())
     {
-        let new_world_position = world_position + camera_in_transform.translation().truncate();
         let (player_transform, player_entity, fire_timer) = q_player.single();
         let player_position = player_transform.translation.truncate();
         let projectile_direction = (new_world_position - player_position).normalize();
 
-        if let Some(fire_timer) = fire_timer {
+        if fire_timer.is_some() || !stats.shurikens {
             return;
         }
 
         if mouse_button.pressed(MouseButton::Left) {
             commands.spawn((
                 Projectile {
-                    velocity: stats.projectile_speed,
-                    direction: projectile_direction,
                     pierce_amount: stats.projectile_piercing,
-                    damage: stats.damage,
                 },
+                KinematicBody::new(1.0, 0.0)
+                    .with_velocity(projectile_direction * stats.projectile_speed),
                 Transform::from_translation(player_position.extend(0.0)),
-                Sprite::from_image(asset_server.load("candycane_shuriken.png")),
+                shuriken_sprite(&asset_server, &stats),
                 Rotate { speed: -30.0 },
             ));
 
@@ -401,6 +638,14 @@ fn fire_projectile(
     }
 }
 
+fn shuriken_sprite(asset_server: &AssetServer, stats: &PlayerStats) -> Sprite {
+    if stats.has_evolution(Evolution::FrostbiteShurikens) {
+        let def = Evolution::FrostbiteShurikens.def();
+        return Sprite::from_image(asset_server.load(def.sprite));
+    }
+    Sprite::from_image(asset_server.load("candycane_shuriken.png"))
+}
+
 fn powerup_player(
     mut commands: Commands,
     mut q_player: Query<(Entity, &mut Sprite, &mut PoweredUp, &mut Blink), With<PoweredUp>>,
@@ -412,7 +657,6 @@ fn powerup_player(
     };
     poweredup.timer.tick(time.delta());
     if poweredup.timer.just_finished() {
-        println!("Powerup expired");
         commands.entity(player_entity).remove::<PoweredUp>();
         commands.entity(player_entity).remove::<Blink>();
         sprite.color = Color::srgba(1., 1., 1., 1.);
@@ -434,22 +678,6 @@ fn shield_movement(
         transform.translation = rotation * transform.translation;
     }
 }
-fn scale_snowball_to_health(
-    mut q_player: Query<(&PlayerHealth, &mut Player)>,
-    mut q_player_snowball: Query<&mut Transform, With<PlayerSnowball>>,
-    mut resolution: ResMut<Resolution>,
-) {
-    let (player_health, mut player) = q_player.single_mut();
-    let mut player_snowball_tf = q_player_snowball.single_mut();
-    if player_health.hp > 10.0 {
-        player_snowball_tf.scale = Vec3::new(player_health.hp / 10.0, player_health.hp / 10.0, 1.0);
-        player.max_velocity = player_health.hp * 10.0;
-    }
-
-    let scale_modifer = (player_snowball_tf.scale.x / 1.5).clamp(1.0, 3.0);
-    resolution.width = (scale_modifer * resolution.base_width as f32).round() as u32;
-    resolution.height = (scale_modifer * resolution.base_height as f32).round() as u32;
-}
 fn kill_player(mut commands: Commands, q_player: Query<(Entity, &PlayerHealth), With<Player>>) {
     let (player_entity, player_health) = q_player.single();
     if player_health.hp <= 0.0 {
@@ -458,18 +686,34 @@ fn kill_player(mut commands: Commands, q_player: Query<(Entity, &PlayerHealth),
         commands.entity(player_entity).despawn_recursive();
     }
 }
+/// Levels up as many times as the XP allows, keeping the overflow, and shows one upgrade
+/// screen per level. Waits for any other screen requested this frame, such as a chest, to
+/// open and close first.
 fn upgrade_player(
     mut q_player: Query<&mut PlayerXp, With<Player>>,
     mut game_state: ResMut<NextState<GameState>>,
-    mut player_stats: ResMut<PlayerStats>,
+    mut run_stats: ResMut<RunStats>,
 ) {
     let Ok(mut player_xp) = q_player.get_single_mut() else {
         return;
     };
-    if player_xp.xp >= player_stats.xp_requirement {
+    while player_xp.xp >= player_xp.requirement() {
+        player_xp.xp -= player_xp.requirement();
+        player_xp.level += 1;
+        player_xp.pending_levels += 1;
+    }
+    if run_stats.level != player_xp.level {
+        run_stats.level = player_xp.level;
+    }
+    if player_xp.pending_levels > 0 && matches!(*game_state, NextState::Unchanged) {
         game_state.set(GameState::Upgrade);
-        player_xp.xp = 0.0;
-        player_stats.xp_requirement += 4.0;
+    }
+}
+
+/// A level is only used up once its upgrade screen actually opens.
+fn spend_pending_level(mut q_player: Query<&mut PlayerXp, With<Player>>) {
+    if let Ok(mut player_xp) = q_player.get_single_mut() {
+        player_xp.pending_levels = player_xp.pending_levels.saturating_sub(1);
     }
 }
 
@@ -515,22 +759,27 @@ fn animate_sprite(
 #[derive(Resource, Default)]
 struct LastShieldCount {
     count: u32,
+    /// Shields are respawned with the roasted look once they evolve
+    roasted: bool,
 }
 
 fn update_shields(
     mut commands: Commands,
     mut q_player: Query<(Entity, &Transform, &mut ShieldCircle), With<Player>>,
     asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     player_stats: Res<PlayerStats>,
     mut last_shield_count: ResMut<LastShieldCount>,
     q_shield: Query<Entity, With<Shield>>,
 ) {
-    let Ok((player_entity, player_transform, mut shield_circle)) = q_player.get_single_mut() else {
+    let Ok((player_entity, _player_transform, mut shield_circle)) = q_player.get_single_mut()
+    else {
         return;
     };
 
     // If the shield count hasn't changed, do nothing.
-    if player_stats.num_shields == last_shield_count.count {
+    let roasted = player_stats.has_evolution(Evolution::RoastedChestnutRing);
+    if player_stats.num_shields == last_shield_count.count && roasted == last_shield_count.roasted {
         return;
     }
     // Otherwise, update ShieldCircle.number to match new count
@@ -542,13 +791,9 @@ fn update_shields(
 
     // 2) Spawn new shields
     for i in 0..shield_circle.number {
-        println!("Spawning {} shields out of {}", shield_circle.number, i);
-
         let child = commands
             .spawn((
-                Shield {
-                    damage: player_stats.shield_damage,
-                },
+                Shield,
                 Transform::from_translation({
                     let angle =
                         (i as f32) * 2.0 * std::f32::consts::PI / (shield_circle.number as f32);
@@ -560,9 +805,30 @@ fn update_shields(
                 YSort { z: 10.0 },
             ))
             .id();
+        if roasted {
+            let def = Evolution::RoastedChestnutRing.def();
+            commands
+                .entity(child)
+                .insert(Sprite::from_image(asset_server.load(def.sprite)));
+            let layout = TextureAtlasLayout::from_grid(UVec2::splat(36), 2, 1, None, None);
+            let animation_indices = AnimationIndices { first: 0, last: 1 };
+            commands.entity(child).with_child((
+                Sprite::from_atlas_image(
+                    asset_server.load("fire.png"),
+                    TextureAtlas {
+                        layout: texture_atlas_layouts.add(layout),
+                        index: animation_indices.first,
+                    },
+                ),
+                Transform::from_xyz(0.0, 4.0, 0.1),
+                animation_indices,
+                AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+            ));
+        }
         commands.entity(player_entity).add_child(child);
     }
 
     // 3) Update our last known shield count
     last_shield_count.count = player_stats.num_shields;
+    last_shield_count.roasted = roasted;
 }
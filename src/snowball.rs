@@ -0,0 +1,152 @@
+use crate::enemy::Enemy;
+use crate::physics::KinematicBody;
+use crate::player::{Player, PlayerSnowball};
+use crate::status::{StatusEffects, StatusKind};
+use crate::GameState;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Mass the snowball starts a run with, and the mass at which it is drawn at 1x scale.
+pub const BASE_MASS: f32 = 10.0;
+/// The snowball never melts below this.
+const MIN_MASS: f32 = 5.0;
+/// Collision radius of the snowball at 1x scale.
+const BASE_COLLISION_RADIUS: f32 = 10.0;
+
+/// Size of one cell of the snow cover grid.
+const SNOW_CELL_SIZE: f32 = 16.0;
+/// Mass gained per pixel rolled over fresh snow.
+const SNOW_PICKUP_RATE: f32 = 0.004;
+/// How much snow a fresh cell holds, enough for exactly one pass.
+const CELL_SNOW: f32 = SNOW_CELL_SIZE * SNOW_PICKUP_RATE;
+/// Seconds for a packed cell to fill back up with fresh snow.
+const SNOW_REGROW_TIME: f32 = 20.0;
+
+/// Below this speed the snowball counts as standing still and starts melting.
+const IDLE_SPEED: f32 = 15.0;
+const IDLE_MELT_RATE: f32 = 0.4;
+/// Mass lost per second for every burning enemy touching the snowball.
+const FIRE_MELT_RATE: f32 = 1.5;
+/// Mass gained for every enemy defeated.
+pub const KILL_MASS: f32 = 0.2;
+
+pub struct SnowballPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for SnowballPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SnowCover::default());
+        app.add_systems(OnEnter(self.state.clone()), reset_snow_cover);
+        app.add_systems(
+            FixedUpdate,
+            (
+                roll_snowball,
+                melt_snowball,
+                regrow_snow,
+                scale_snowball_to_mass,
+            )
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct SnowballMass {
+    pub mass: f32,
+}
+
+impl SnowballMass {
+    pub fn scale(&self) -> f32 {
+        self.mass / BASE_MASS
+    }
+    pub fn collision_radius(&self) -> f32 {
+        BASE_COLLISION_RADIUS * self.scale()
+    }
+    pub fn add(&mut self, amount: f32) {
+        self.mass = (self.mass + amount).max(MIN_MASS);
+    }
+}
+
+/// Snow left in every cell the snowball has rolled over. Cells that are missing are fresh.
+#[derive(Resource, Default)]
+struct SnowCover {
+    packed: HashMap<IVec2, f32>,
+}
+
+fn reset_snow_cover(mut snow_cover: ResMut<SnowCover>) {
+    snow_cover.packed.clear();
+}
+
+fn roll_snowball(
+    mut q_player: Query<(&Transform, &KinematicBody, &mut SnowballMass), With<Player>>,
+    mut snow_cover: ResMut<SnowCover>,
+    time: Res<Time>,
+) {
+    let Ok((transform, body, mut snowball)) = q_player.get_single_mut() else {
+        return;
+    };
+    let cell = (transform.translation.truncate() / SNOW_CELL_SIZE)
+        .floor()
+        .as_ivec2();
+    let snow_left = snow_cover.packed.entry(cell).or_insert(CELL_SNOW);
+
+    let picked_up = (body.velocity.length() * time.delta_secs() * SNOW_PICKUP_RATE).min(*snow_left);
+    *snow_left -= picked_up;
+    snowball.add(picked_up);
+}
+
+fn melt_snowball(
+    mut q_player: Query<(&KinematicBody, &mut SnowballMass), With<Player>>,
+    q_player_snowball: Query<&GlobalTransform, With<PlayerSnowball>>,
+    q_enemy: Query<(&Transform, &StatusEffects), With<Enemy>>,
+    time: Res<Time>,
+) {
+    let Ok((body, mut snowball)) = q_player.get_single_mut() else {
+        return;
+    };
+    let Ok(snowball_tf) = q_player_snowball.get_single() else {
+        return;
+    };
+    let dt = time.delta_secs();
+
+    if body.velocity.length() < IDLE_SPEED {
+        snowball.add(-IDLE_MELT_RATE * dt);
+    }
+
+    let snowball_pos = snowball_tf.translation().truncate();
+    let radius = snowball.collision_radius();
+    let touching_fire = q_enemy
+        .iter()
+        .filter(|(tf, statuses)| {
+            statuses.has(StatusKind::Burning)
+                && tf.translation.truncate().distance(snowball_pos) < radius
+        })
+        .count();
+    snowball.add(-FIRE_MELT_RATE * touching_fire as f32 * dt);
+}
+
+fn regrow_snow(mut snow_cover: ResMut<SnowCover>, time: Res<Time>) {
+    let regrown = CELL_SNOW / SNOW_REGROW_TIME * time.delta_secs();
+    snow_cover.packed.retain(|_, snow_left| {
+        *snow_left += regrown;
+        *snow_left < CELL_SNOW
+    });
+}
+
+fn scale_snowball_to_mass(
+    mut q_player: Query<(&SnowballMass, &mut KinematicBody), With<Player>>,
+    mut q_player_snowball: Query<&mut Transform, With<PlayerSnowball>>,
+) {
+    let Ok((snowball, mut body)) = q_player.get_single_mut() else {
+        return;
+    };
+    let Ok(mut player_snowball_tf) = q_player_snowball.get_single_mut() else {
+        return;
+    };
+    let scale = snowball.scale();
+    player_snowball_tf.scale = Vec3::new(scale, scale, 1.0);
+    body.mass = snowball.mass;
+    body.max_speed = Some(snowball.mass.max(BASE_MASS) * 10.0);
+}
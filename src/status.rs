@@ -0,0 +1,724 @@
+use crate::collision::Blink;
+use crate::collision::FlashingTimer;
+use crate::damage_numbers::DamageEvent;
+use crate::damage_numbers::DamageKind;
+use crate::enemy::EnemyHealth;
+use crate::enemy::EnemyVisual;
+use crate::juice::Impact;
+use crate::player::AnimationIndices;
+use crate::player::AnimationTimer;
+use crate::player::PlayerStats;
+use crate::utils::YSort;
+use crate::GameState;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+/// Long enough for a shock to show up on the enemy and combine with other effects.
+const SHOCK_DURATION: f32 = 0.5;
+
+pub struct StatusPlugin<S: States> {
+    pub state: S,
+}
+
+impl<S: States> Plugin for StatusPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApplyStatus>();
+        app.insert_resource(StatusRegistry::default());
+        app.add_systems(
+            FixedUpdate,
+            (apply_statuses, tick_statuses)
+                .chain()
+                .run_if(in_state(self.state.clone()))
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StatusKind {
+    Frozen,
+    Burning,
+    /// Takes extra damage from shurikens, left behind by freezer burn
+    Vulnerable,
+    /// Stacking slow that turns into Frozen at enough stacks
+    Chilled,
+    /// Instant damage that arcs to nearby enemies; lingers briefly for combos
+    Shocked,
+    /// Peppermint poison, stacking damage over time that spreads on death
+    Poisoned,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StatusSource {
+    Projectile,
+    Shield,
+    FreezeTrail,
+    Pickup,
+    /// Applied by another effect or an interaction between two effects
+    Reaction,
+}
+
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub stacks: u32,
+    pub duration: f32,
+    pub max_duration: f32,
+    /// Effect specific strength, e.g. the damage multiplier of Vulnerable
+    pub potency: f32,
+    pub source: StatusSource,
+    /// Sprite shown on the target while the effect lasts, despawned when it expires
+    pub visual: Option<Entity>,
+}
+
+/// Every effect currently on an enemy.
+#[derive(Component, Default)]
+pub struct StatusEffects {
+    pub effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn get(&self, kind: StatusKind) -> Option<&StatusEffect> {
+        self.effects.iter().find(|effect| effect.kind == kind)
+    }
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.get(kind).is_some()
+    }
+    /// Ends an effect on the next tick, running its expire hook as usual.
+    pub fn expire(&mut self, kind: StatusKind) {
+        for effect in self.effects.iter_mut().filter(|effect| effect.kind == kind) {
+            effect.duration = 0.0;
+        }
+    }
+}
+
+/// Sent to put an effect on an enemy; interactions and stacking are resolved by the registry.
+#[derive(Event, Clone, Copy)]
+pub struct ApplyStatus {
+    pub target: Entity,
+    pub kind: StatusKind,
+    pub source: StatusSource,
+}
+
+/// Everything a hook may touch on the target and the world around it.
+pub struct StatusContext<'a, 'w, 's> {
+    pub commands: &'a mut Commands<'w, 's>,
+    pub entity: Entity,
+    /// The enemy's main sprite, for tinting and blinking
+    pub sprite: Entity,
+    pub position: Vec2,
+    /// The target's health; only written back to `EnemyHealth` if a hook dealt damage
+    pub health: f32,
+    pub stats: &'a PlayerStats,
+    pub asset_server: &'a AssetServer,
+    pub atlas_layouts: &'a mut Assets<TextureAtlasLayout>,
+    pub dt: f32,
+    /// Other enemies and their positions, for effects that reach beyond the target
+    pub nearby: &'a [(Entity, Vec2)],
+    pub damage: Vec<DamageEvent>,
+    /// Screen shake and hit-stop a hook wants to trigger
+    pub impacts: Vec<Impact>,
+    /// Effects a hook wants applied to the same target once it returns
+    pub follow_ups: Vec<StatusKind>,
+    /// Effects a hook wants applied to other enemies
+    pub spread: Vec<(Entity, StatusKind)>,
+}
+
+impl StatusContext<'_, '_, '_> {
+    pub fn deal_damage(&mut self, amount: f32, kind: DamageKind, over_time: bool) {
+        self.health -= amount;
+        self.damage.push(DamageEvent {
+            target: self.entity,
+            position: self.position,
+            amount,
+            kind,
+            over_time,
+        });
+    }
+}
+
+pub type StatusHook = fn(&mut StatusContext, &mut StatusEffect);
+/// Gets the target and the effect that was already on it.
+pub type InteractionHook = fn(&mut StatusContext, &StatusEffect);
+
+#[derive(Clone, Copy)]
+pub enum Reapply {
+    /// Applying an effect the target already has does nothing
+    Ignore,
+    Refresh,
+    /// Adds a stack up to `max` and refreshes the duration, running on_apply again
+    Stack {
+        max: u32,
+    },
+}
+
+pub struct StatusDef {
+    pub kind: StatusKind,
+    pub icon: &'static str,
+    pub icon_color: Color,
+    pub reapply: Reapply,
+    pub duration: fn(&PlayerStats) -> f32,
+    pub potency: fn(&PlayerStats) -> f32,
+    pub on_apply: Option<StatusHook>,
+    pub on_tick: Option<StatusHook>,
+    pub on_expire: Option<StatusHook>,
+    /// Radius the effect jumps to other enemies in when its target dies
+    pub spreads_on_death: Option<fn(&PlayerStats) -> f32>,
+}
+
+/// Applying `incoming` to a target that has `existing` triggers `reaction`.
+pub struct StatusInteraction {
+    pub existing: StatusKind,
+    pub incoming: StatusKind,
+    /// Whether `existing` is removed by the reaction
+    pub consumes_existing: bool,
+    /// The reaction only happens while this holds, otherwise both statuses coexist
+    pub requires: Option<fn(&PlayerStats) -> bool>,
+    pub reaction: InteractionHook,
+}
+
+#[derive(Resource)]
+pub struct StatusRegistry {
+    pub defs: Vec<StatusDef>,
+    pub interactions: Vec<StatusInteraction>,
+}
+
+impl StatusRegistry {
+    pub fn register(&mut self, def: StatusDef) {
+        self.defs.push(def);
+    }
+    pub fn interact(&mut self, interaction: StatusInteraction) {
+        self.interactions.push(interaction);
+    }
+    pub fn def(&self, kind: StatusKind) -> &StatusDef {
+        self.defs
+            .iter()
+            .find(|def| def.kind == kind)
+            .expect("status effect is not registered")
+    }
+
+    /// Applies `kind` and then anything the hooks queued up in response.
+    pub fn apply(
+        &self,
+        ctx: &mut StatusContext,
+        statuses: &mut StatusEffects,
+        kind: StatusKind,
+        source: StatusSource,
+    ) {
+        self.apply_one(ctx, statuses, kind, source);
+        while let Some(follow_up) = ctx.follow_ups.pop() {
+            self.apply_one(ctx, statuses, follow_up, StatusSource::Reaction);
+        }
+    }
+
+    fn apply_one(
+        &self,
+        ctx: &mut StatusContext,
+        statuses: &mut StatusEffects,
+        kind: StatusKind,
+        source: StatusSource,
+    ) {
+        for interaction in self
+            .interactions
+            .iter()
+            .filter(|interaction| interaction.incoming == kind)
+            .filter(|interaction| {
+                interaction
+                    .requires
+                    .is_none_or(|requires| requires(ctx.stats))
+            })
+        {
+            let Some(index) = statuses
+                .effects
+                .iter()
+                .position(|effect| effect.kind == interaction.existing)
+            else {
+                continue;
+            };
+            if interaction.consumes_existing {
+                let mut existing = statuses.effects.remove(index);
+                self.expire(ctx, &mut existing);
+                (interaction.reaction)(ctx, &existing);
+            } else {
+                (interaction.reaction)(ctx, &statuses.effects[index]);
+            }
+        }
+
+        let def = self.def(kind);
+        if let Some(existing) = statuses
+            .effects
+            .iter_mut()
+            .find(|effect| effect.kind == kind)
+        {
+            match def.reapply {
+                Reapply::Ignore => {}
+                Reapply::Refresh => existing.duration = existing.max_duration,
+                Reapply::Stack { max } => {
+                    existing.stacks = (existing.stacks + 1).min(max);
+                    existing.duration = existing.max_duration;
+                    if let Some(on_apply) = def.on_apply {
+                        on_apply(ctx, existing);
+                    }
+                }
+            }
+            return;
+        }
+
+        let duration = (def.duration)(ctx.stats);
+        let mut effect = StatusEffect {
+            kind,
+            stacks: 1,
+            duration,
+            max_duration: duration,
+            potency: (def.potency)(ctx.stats),
+            source,
+            visual: None,
+        };
+        if let Some(on_apply) = def.on_apply {
+            on_apply(ctx, &mut effect);
+        }
+        statuses.effects.push(effect);
+    }
+
+    fn expire(&self, ctx: &mut StatusContext, effect: &mut StatusEffect) {
+        if let Some(on_expire) = self.def(effect.kind).on_expire {
+            on_expire(ctx, effect);
+        }
+        if let Some(visual) = effect.visual.take() {
+            ctx.commands.entity(visual).despawn_recursive();
+        }
+    }
+}
+
+impl Default for StatusRegistry {
+    fn default() -> Self {
+        let mut registry = Self {
+            defs: Vec::new(),
+            interactions: Vec::new(),
+        };
+        registry.register(StatusDef {
+            kind: StatusKind::Frozen,
+            icon: "freeze.png",
+            icon_color: Color::WHITE,
+            reapply: Reapply::Ignore,
+            duration: |stats| stats.freeze_duration,
+            potency: |_| 0.0,
+            on_apply: Some(freeze),
+            on_tick: None,
+            on_expire: None,
+            spreads_on_death: None,
+        });
+        registry.register(StatusDef {
+            kind: StatusKind::Burning,
+            icon: "fire_icon.png",
+            icon_color: Color::WHITE,
+            reapply: Reapply::Ignore,
+            duration: |stats| stats.fire_duration,
+            potency: |stats| stats.fire_dps,
+            on_apply: Some(ignite),
+            on_tick: Some(burn),
+            on_expire: Some(extinguish),
+            spreads_on_death: None,
+        });
+        registry.register(StatusDef {
+            kind: StatusKind::Vulnerable,
+            icon: "freeze.png",
+            icon_color: Color::srgb(0.8, 0.3, 1.0),
+            reapply: Reapply::Refresh,
+            duration: |stats| stats.freezer_burn_duration,
+            potency: |stats| stats.freezer_burn_multiplier,
+            on_apply: None,
+            on_tick: None,
+            on_expire: None,
+            spreads_on_death: None,
+        });
+        registry.register(StatusDef {
+            kind: StatusKind::Chilled,
+            icon: "freeze.png",
+            icon_color: Color::srgb(0.5, 0.8, 1.0),
+            reapply: Reapply::Stack { max: u32::MAX },
+            duration: |stats| stats.chill_duration,
+            potency: |stats| stats.chill_slow_per_stack,
+            on_apply: Some(chill),
+            on_tick: None,
+            on_expire: None,
+            spreads_on_death: None,
+        });
+        registry.register(StatusDef {
+            kind: StatusKind::Shocked,
+            icon: "shock_icon.png",
+            icon_color: Color::srgb(1.0, 0.95, 0.3),
+            reapply: Reapply::Ignore,
+            duration: |_| SHOCK_DURATION,
+            potency: |stats| stats.shock_damage,
+            on_apply: Some(shock),
+            on_tick: None,
+            on_expire: None,
+            spreads_on_death: None,
+        });
+        registry.register(StatusDef {
+            kind: StatusKind::Poisoned,
+            icon: "Candycane.png",
+            icon_color: Color::srgb(0.4, 1.0, 0.5),
+            reapply: Reapply::Stack { max: u32::MAX },
+            duration: |stats| stats.poison_duration,
+            potency: |stats| stats.poison_dps,
+            on_apply: Some(poison),
+            on_tick: Some(poison_tick),
+            on_expire: None,
+            spreads_on_death: Some(|stats| stats.poison_spread_radius),
+        });
+        registry.interact(StatusInteraction {
+            existing: StatusKind::Burning,
+            incoming: StatusKind::Frozen,
+            consumes_existing: true,
+            requires: None,
+            reaction: flash_freeze,
+        });
+        registry.interact(StatusInteraction {
+            existing: StatusKind::Frozen,
+            incoming: StatusKind::Burning,
+            consumes_existing: true,
+            requires: None,
+            reaction: freezer_burn,
+        });
+        registry.interact(StatusInteraction {
+            existing: StatusKind::Chilled,
+            incoming: StatusKind::Shocked,
+            consumes_existing: false,
+            requires: None,
+            reaction: superconduct,
+        });
+        registry.interact(StatusInteraction {
+            existing: StatusKind::Poisoned,
+            incoming: StatusKind::Burning,
+            consumes_existing: true,
+            requires: Some(|stats| stats.toxic_flare),
+            reaction: toxic_flare,
+        });
+        registry
+    }
+}
+
+fn freeze(ctx: &mut StatusContext, effect: &mut StatusEffect) {
+    let freeze_sprite = ctx
+        .commands
+        .spawn((
+            Sprite::from_image(ctx.asset_server.load("freeze.png")),
+            YSort { z: 0.6 },
+        ))
+        .id();
+    ctx.commands.entity(ctx.entity).add_child(freeze_sprite);
+    effect.visual = Some(freeze_sprite);
+}
+
+fn ignite(ctx: &mut StatusContext, effect: &mut StatusEffect) {
+    ctx.commands.entity(ctx.sprite).insert(Blink {
+        color: Color::srgba(12., 12., 12., 1.),
+        speed: 10.0,
+    });
+
+    let texture = ctx.asset_server.load("fire.png");
+    let layout = TextureAtlasLayout::from_grid(UVec2::splat(36), 2, 1, None, None);
+    let texture_atlas_layout = ctx.atlas_layouts.add(layout);
+    let animation_indices = AnimationIndices { first: 0, last: 1 };
+    let fire_sprite = ctx
+        .commands
+        .spawn((
+            YSort { z: 0.6 },
+            Sprite::from_atlas_image(
+                texture,
+                TextureAtlas {
+                    layout: texture_atlas_layout,
+                    index: animation_indices.first,
+                },
+            ),
+            animation_indices,
+            AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+        ))
+        .id();
+    ctx.commands.entity(ctx.entity).add_child(fire_sprite);
+    effect.visual = Some(fire_sprite);
+}
+
+fn burn(ctx: &mut StatusContext, effect: &mut StatusEffect) {
+    ctx.deal_damage(effect.potency * ctx.dt, DamageKind::Fire, true);
+}
+
+fn extinguish(ctx: &mut StatusContext, _effect: &mut StatusEffect) {
+    ctx.commands.entity(ctx.sprite).remove::<Blink>();
+    ctx.commands.entity(ctx.sprite).insert(FlashingTimer {
+        time_left: 0.0,
+        color: Color::srgba(1., 1., 1., 1.),
+    });
+}
+
+/// Enough stacks freeze the target solid.
+fn chill(ctx: &mut StatusContext, effect: &mut StatusEffect) {
+    if effect.stacks >= ctx.stats.chill_stacks_to_freeze {
+        effect.duration = 0.0;
+        ctx.follow_ups.push(StatusKind::Frozen);
+    }
+}
+
+/// Direct shocks arc to the closest enemies in range; the arcs themselves don't chain again.
+fn shock(ctx: &mut StatusContext, effect: &mut StatusEffect) {
+    ctx.deal_damage(effect.potency, DamageKind::Shock, false);
+    if effect.source == StatusSource::Reaction {
+        return;
+    }
+    let mut targets: Vec<(Entity, f32)> = ctx
+        .nearby
+        .iter()
+        .filter(|(entity, _)| *entity != ctx.entity)
+        .map(|(entity, position)| (*entity, position.distance(ctx.position)))
+        .filter(|(_, distance)| *distance < ctx.stats.shock_range)
+        .collect();
+    targets.sort_by(|a, b| a.1.total_cmp(&b.1));
+    for (entity, _) in targets.into_iter().take(ctx.stats.shock_chains) {
+        ctx.spread.push((entity, StatusKind::Shocked));
+    }
+}
+
+fn poison(ctx: &mut StatusContext, effect: &mut StatusEffect) {
+    effect.stacks = effect.stacks.min(ctx.stats.poison_max_stacks);
+}
+
+fn poison_tick(ctx: &mut StatusContext, effect: &mut StatusEffect) {
+    let damage = effect.potency * effect.stacks as f32 * ctx.dt;
+    ctx.deal_damage(damage, DamageKind::Poison, true);
+}
+
+/// Freezing a burning enemy deals a share of its remaining health.
+fn flash_freeze(ctx: &mut StatusContext, _burning: &StatusEffect) {
+    if !ctx.stats.flash_freeze {
+        return;
+    }
+    let damage = ctx.health * ctx.stats.flash_freeze_percent_damage;
+    ctx.deal_damage(damage, DamageKind::Freeze, false);
+    ctx.impacts.push(Impact::FLASH_FREEZE);
+}
+
+/// Burning a frozen enemy leaves it vulnerable.
+fn freezer_burn(ctx: &mut StatusContext, _frozen: &StatusEffect) {
+    if ctx.stats.freezer_burn {
+        ctx.follow_ups.push(StatusKind::Vulnerable);
+    }
+}
+
+/// Shocking a chilled enemy hits it again for the superconduct multiplier.
+fn superconduct(ctx: &mut StatusContext, chilled: &StatusEffect) {
+    if !ctx.stats.superconduct {
+        return;
+    }
+    let damage = ctx.stats.shock_damage * ctx.stats.superconduct_multiplier * chilled.stacks as f32;
+    ctx.deal_damage(damage, DamageKind::Shock, false);
+}
+
+/// Setting a poisoned enemy alight burns off all of its poison at once.
+fn toxic_flare(ctx: &mut StatusContext, poisoned: &StatusEffect) {
+    let damage = ctx.stats.toxic_flare_damage * poisoned.stacks as f32;
+    ctx.deal_damage(damage, DamageKind::Poison, false);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn apply_statuses(
+    mut commands: Commands,
+    mut apply_events: EventReader<ApplyStatus>,
+    mut q_target: Query<(
+        &mut StatusEffects,
+        &mut EnemyHealth,
+        &Transform,
+        &EnemyVisual,
+    )>,
+    q_nearby: Query<(Entity, &Transform), With<StatusEffects>>,
+    registry: Res<StatusRegistry>,
+    stats: Res<PlayerStats>,
+    asset_server: Res<AssetServer>,
+    mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut impacts: EventWriter<Impact>,
+    time: Res<Time>,
+) {
+    let mut queue: VecDeque<ApplyStatus> = apply_events.read().copied().collect();
+    if queue.is_empty() {
+        return;
+    }
+    let nearby: Vec<(Entity, Vec2)> = q_nearby
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation.truncate()))
+        .collect();
+    while let Some(event) = queue.pop_front() {
+        let Ok((mut statuses, mut health, transform, visual)) = q_target.get_mut(event.target)
+        else {
+            continue;
+        };
+        let mut ctx = StatusContext {
+            commands: &mut commands,
+            entity: event.target,
+            sprite: visual.0,
+            position: transform.translation.truncate(),
+            health: health.health,
+            stats: &stats,
+            asset_server: &asset_server,
+            atlas_layouts: &mut atlas_layouts,
+            dt: time.delta_secs(),
+            nearby: &nearby,
+            damage: Vec::new(),
+            impacts: Vec::new(),
+            follow_ups: Vec::new(),
+            spread: Vec::new(),
+        };
+        registry.apply(&mut ctx, &mut statuses, event.kind, event.source);
+        if !ctx.damage.is_empty() {
+            health.health = ctx.health;
+        }
+        damage_events.send_batch(ctx.damage);
+        impacts.send_batch(ctx.impacts);
+        queue.extend(ctx.spread.into_iter().map(|(target, kind)| ApplyStatus {
+            target,
+            kind,
+            source: StatusSource::Reaction,
+        }));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn tick_statuses(
+    mut commands: Commands,
+    mut q_target: Query<(
+        Entity,
+        &mut StatusEffects,
+        &mut EnemyHealth,
+        &Transform,
+        &EnemyVisual,
+    )>,
+    registry: Res<StatusRegistry>,
+    stats: Res<PlayerStats>,
+    asset_server: Res<AssetServer>,
+    mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut apply_status: EventWriter<ApplyStatus>,
+    mut impacts: EventWriter<Impact>,
+    time: Res<Time>,
+) {
+    for (entity, mut statuses, mut health, transform, visual) in q_target.iter_mut() {
+        if statuses.effects.is_empty() {
+            continue;
+        }
+        let mut ctx = StatusContext {
+            commands: &mut commands,
+            entity,
+            sprite: visual.0,
+            position: transform.translation.truncate(),
+            health: health.health,
+            stats: &stats,
+            asset_server: &asset_server,
+            atlas_layouts: &mut atlas_layouts,
+            dt: time.delta_secs(),
+            nearby: &[],
+            damage: Vec::new(),
+            impacts: Vec::new(),
+            follow_ups: Vec::new(),
+            spread: Vec::new(),
+        };
+
+        let mut effects = std::mem::take(&mut statuses.effects);
+        effects.retain_mut(|effect| {
+            effect.duration -= ctx.dt;
+            if let Some(on_tick) = registry.def(effect.kind).on_tick {
+                on_tick(&mut ctx, effect);
+            }
+            if effect.duration > 0.0 {
+                return true;
+            }
+            registry.expire(&mut ctx, effect);
+            false
+        });
+        statuses.effects = effects;
+
+        while let Some(follow_up) = ctx.follow_ups.pop() {
+            registry.apply(&mut ctx, &mut statuses, follow_up, StatusSource::Reaction);
+        }
+        // Only touch EnemyHealth when it really changed, so health bars can rely on change detection
+        if !ctx.damage.is_empty() {
+            health.health = ctx.health;
+        }
+        damage_events.send_batch(ctx.damage);
+        impacts.send_batch(ctx.impacts);
+        apply_status.send_batch(ctx.spread.into_iter().map(|(target, kind)| ApplyStatus {
+            target,
+            kind,
+            source: StatusSource::Reaction,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collision::{blinking, flashing};
+
+    /// Spawns a snowman shaped like the real ones: a holder with a shadow and a main sprite.
+    fn spawn_enemy(app: &mut App) -> (Entity, Entity, Entity) {
+        let world = app.world_mut();
+        let sprite = world.spawn(Sprite::default()).id();
+        let shadow = world.spawn(Sprite::default()).id();
+        let holder = world
+            .spawn((
+                Transform::default(),
+                EnemyHealth {
+                    health: 100.,
+                    max_health: 100.,
+                },
+                StatusEffects::default(),
+                EnemyVisual(sprite),
+            ))
+            .add_children(&[shadow, sprite])
+            .id();
+        (holder, shadow, sprite)
+    }
+
+    fn apply(app: &mut App, target: Entity, kind: StatusKind) {
+        app.world_mut().send_event(ApplyStatus {
+            target,
+            kind,
+            source: StatusSource::Projectile,
+        });
+        app.update();
+    }
+
+    fn color(app: &App, entity: Entity) -> Color {
+        app.world().get::<Sprite>(entity).unwrap().color
+    }
+
+    #[test]
+    fn status_tints_only_touch_the_enemy_visual() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_asset::<TextureAtlasLayout>()
+            .add_event::<ApplyStatus>()
+            .add_event::<DamageEvent>()
+            .add_event::<Impact>()
+            .insert_resource(StatusRegistry::default())
+            .insert_resource(PlayerStats::default())
+            .add_systems(Update, (apply_statuses, blinking, flashing).chain());
+        let (holder, shadow, sprite) = spawn_enemy(&mut app);
+
+        apply(&mut app, holder, StatusKind::Burning);
+        assert!(app.world().get::<Blink>(sprite).is_some());
+        assert_ne!(color(&app, sprite), Color::WHITE);
+        assert_eq!(color(&app, shadow), Color::WHITE);
+        assert!(app.world().get::<Blink>(holder).is_none());
+
+        // Freezing puts the fire out, which stops the blink and resets the tint
+        apply(&mut app, holder, StatusKind::Frozen);
+        app.update();
+        assert!(app.world().get::<Blink>(sprite).is_none());
+        assert_eq!(color(&app, sprite), Color::WHITE);
+        assert_eq!(color(&app, shadow), Color::WHITE);
+        let statuses = app.world().get::<StatusEffects>(holder).unwrap();
+        assert!(statuses.has(StatusKind::Frozen));
+        assert!(!statuses.has(StatusKind::Burning));
+    }
+}
@@ -1,5 +1,14 @@
+use crate::character::RunStats;
+use crate::dash::DashCharges;
+use crate::mainmenu::TakenUpgrades;
+use crate::player::FireTimer;
+use crate::player::Player;
+use crate::player::PlayerHealth;
 use crate::player::PlayerStats;
 use crate::player::PlayerXp;
+use crate::player::PoweredUp;
+use crate::player::PLAYER_START_HP;
+use crate::snowball::SnowballMass;
 use crate::GameState;
 use bevy::prelude::*;
 use bevy::ui::widget::NodeImageMode;
@@ -12,7 +21,16 @@ impl<S: States> Plugin for UiPlugin<S> {
         app.add_systems(OnEnter(self.state.clone()), setup_ui);
         app.add_systems(
             FixedUpdate,
-            update_xp_bar
+            (
+                update_xp_bar,
+                update_health,
+                update_mass_text,
+                update_dash_indicator,
+                update_shuriken_cooldown,
+                update_sugar_rush_text,
+                update_run_stats,
+                update_upgrade_icons,
+            )
                 .run_if(in_state(self.state.clone()))
                 .run_if(in_state(GameState::Playing)),
         );
@@ -21,17 +39,264 @@ impl<S: States> Plugin for UiPlugin<S> {
 }
 #[derive(Component)]
 struct XpBar;
+#[derive(Component)]
+struct HealthText;
+#[derive(Component)]
+struct HealthBar;
+#[derive(Component)]
+struct MassText;
+#[derive(Component)]
+struct DashText;
+#[derive(Component)]
+struct DashBar;
+#[derive(Component)]
+struct ShurikenBar;
+/// Holds the shuriken label and bar, hidden for characters without shurikens
+#[derive(Component)]
+struct ShurikenHud;
+#[derive(Component)]
+struct SugarRushText;
+#[derive(Component)]
+struct LevelText;
+#[derive(Component)]
+struct TimeText;
+#[derive(Component)]
+struct KillText;
+#[derive(Component)]
+struct UpgradeIconRow;
+
+/// Only touches the text when it actually differs, so unchanged labels don't get re-laid out.
+fn set_text(text: &mut Mut<Text>, value: String) {
+    if text.0 != value {
+        text.0 = value;
+    }
+}
+fn set_bar(bar: &mut Mut<Node>, fraction: f32) {
+    let width = Val::Percent(fraction.clamp(0.0, 1.0) * 100.0);
+    if bar.width != width {
+        bar.width = width;
+    }
+}
+
 fn update_xp_bar(
-    player_q: Query<&PlayerXp>,
+    player_q: Query<&PlayerXp, Changed<PlayerXp>>,
     mut xp_bar_q: Query<&mut Node, With<XpBar>>,
+) {
+    let Ok(player_xp) = player_q.get_single() else {
+        return;
+    };
+    let Ok(mut xp_bar) = xp_bar_q.get_single_mut() else {
+        return;
+    };
+    xp_bar.width = Val::Px((player_xp.xp / player_xp.requirement()).min(1.0) * 600.0);
+}
+fn update_health(
+    player_q: Query<&PlayerHealth, Changed<PlayerHealth>>,
+    mut health_text_q: Query<&mut Text, With<HealthText>>,
+    mut health_bar_q: Query<&mut Node, With<HealthBar>>,
+) {
+    let Ok(player_health) = player_q.get_single() else {
+        return;
+    };
+    if let Ok(mut health_text) = health_text_q.get_single_mut() {
+        set_text(
+            &mut health_text,
+            format!("HP {:.1}", player_health.hp.max(0.0)),
+        );
+    }
+    if let Ok(mut health_bar) = health_bar_q.get_single_mut() {
+        set_bar(&mut health_bar, player_health.hp / PLAYER_START_HP);
+    }
+}
+fn update_mass_text(
+    player_q: Query<&SnowballMass, Changed<SnowballMass>>,
+    mut mass_text_q: Query<&mut Text, With<MassText>>,
+) {
+    let Ok(snowball) = player_q.get_single() else {
+        return;
+    };
+    let Ok(mut mass_text) = mass_text_q.get_single_mut() else {
+        return;
+    };
+    set_text(&mut mass_text, format!("Mass {:.1}", snowball.mass));
+}
+fn update_dash_indicator(
+    player_q: Query<&DashCharges, Changed<DashCharges>>,
+    mut dash_text_q: Query<&mut Text, With<DashText>>,
+    mut dash_bar_q: Query<&mut Node, With<DashBar>>,
+    player_stats: Res<PlayerStats>,
+) {
+    let Ok(dash) = player_q.get_single() else {
+        return;
+    };
+    if let Ok(mut dash_text) = dash_text_q.get_single_mut() {
+        set_text(
+            &mut dash_text,
+            format!("Dash {}/{}", dash.charges, player_stats.dash_charges),
+        );
+    }
+    if let Ok(mut dash_bar) = dash_bar_q.get_single_mut() {
+        let fraction = if dash.charges >= player_stats.dash_charges {
+            1.0
+        } else {
+            dash.recharge.fraction()
+        };
+        set_bar(&mut dash_bar, fraction);
+    }
+}
+fn update_shuriken_cooldown(
+    player_q: Query<Option<&FireTimer>, With<Player>>,
+    mut shuriken_bar_q: Query<&mut Node, (With<ShurikenBar>, Without<ShurikenHud>)>,
+    mut shuriken_hud_q: Query<&mut Node, With<ShurikenHud>>,
     player_stats: Res<PlayerStats>,
 ) {
-    let player_xp = player_q.single();
-    let mut xp_bar = xp_bar_q.single_mut();
-    xp_bar.width = Val::Px((player_xp.xp / player_stats.xp_requirement) * 600.0);
+    let Ok(fire_timer) = player_q.get_single() else {
+        return;
+    };
+    // Shurikens can be picked up mid-run, so this is checked every step
+    if let Ok(mut shuriken_hud) = shuriken_hud_q.get_single_mut() {
+        let display = if player_stats.shurikens {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if shuriken_hud.display != display {
+            shuriken_hud.display = display;
+        }
+    }
+    let Ok(mut shuriken_bar) = shuriken_bar_q.get_single_mut() else {
+        return;
+    };
+    let fraction = fire_timer.map_or(1.0, |fire_timer| fire_timer.timer.fraction());
+    set_bar(&mut shuriken_bar, fraction);
+}
+fn update_sugar_rush_text(
+    player_q: Query<Option<&PoweredUp>, With<Player>>,
+    mut sugar_rush_text_q: Query<&mut Text, With<SugarRushText>>,
+) {
+    let Ok(powered_up) = player_q.get_single() else {
+        return;
+    };
+    let Ok(mut sugar_rush_text) = sugar_rush_text_q.get_single_mut() else {
+        return;
+    };
+    let text = match powered_up {
+        Some(powered_up) => format!(
+            "Sugar Rush {:.0}s",
+            powered_up.timer.remaining_secs().ceil()
+        ),
+        None => String::new(),
+    };
+    set_text(&mut sugar_rush_text, text);
+}
+#[allow(clippy::type_complexity)]
+fn update_run_stats(
+    run_stats: Res<RunStats>,
+    mut level_text_q: Query<&mut Text, (With<LevelText>, Without<TimeText>, Without<KillText>)>,
+    mut time_text_q: Query<&mut Text, (With<TimeText>, Without<KillText>)>,
+    mut kill_text_q: Query<&mut Text, With<KillText>>,
+) {
+    if !run_stats.is_changed() {
+        return;
+    }
+    if let Ok(mut level_text) = level_text_q.get_single_mut() {
+        set_text(&mut level_text, format!("Level {}", run_stats.level));
+    }
+    if let Ok(mut time_text) = time_text_q.get_single_mut() {
+        let seconds = run_stats.time as u32;
+        set_text(
+            &mut time_text,
+            format!("{:02}:{:02}", seconds / 60, seconds % 60),
+        );
+    }
+    if let Ok(mut kill_text) = kill_text_q.get_single_mut() {
+        set_text(&mut kill_text, format!("Kills {}", run_stats.kills));
+    }
+}
+fn update_upgrade_icons(
+    mut commands: Commands,
+    taken_upgrades: Res<TakenUpgrades>,
+    icon_row_q: Query<Entity, With<UpgradeIconRow>>,
+    asset_server: Res<AssetServer>,
+) {
+    if !taken_upgrades.is_changed() {
+        return;
+    }
+    let Ok(icon_row) = icon_row_q.get_single() else {
+        return;
+    };
+    commands.entity(icon_row).despawn_descendants();
+    commands.entity(icon_row).with_children(|parent| {
+        for taken in &taken_upgrades.upgrades {
+            parent
+                .spawn((
+                    ImageNode::new(taken.image.clone()),
+                    Node {
+                        width: Val::Px(32.0),
+                        height: Val::Px(32.0),
+                        margin: UiRect::horizontal(Val::Px(4.0)),
+                        justify_content: JustifyContent::End,
+                        align_items: AlignItems::End,
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    if taken.count > 1 {
+                        parent.spawn((
+                            Text::new(format!("{}", taken.count)),
+                            TextFont {
+                                font: asset_server.load("fonts/slkscr.ttf"),
+                                font_size: 14.0,
+                                ..default()
+                            },
+                            TextColor(Color::BLACK),
+                        ));
+                    }
+                });
+        }
+    });
+}
+
+fn spawn_text(parent: &mut ChildBuilder, font: &Handle<Font>, text: &str, marker: impl Bundle) {
+    parent.spawn((
+        Text::new(text),
+        TextFont {
+            font: font.clone(),
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::BLACK),
+        marker,
+    ));
+}
+fn spawn_bar(parent: &mut ChildBuilder, width: f32, color: Color, marker: impl Component) {
+    parent
+        .spawn((
+            Node {
+                margin: UiRect::bottom(Val::Px(6.0)),
+                width: Val::Px(width),
+                height: Val::Px(14.0),
+                border: UiRect::all(Val::Px(3.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.3, 0.3, 0.3, 0.5)),
+            BorderColor(Color::BLACK),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(color),
+                marker,
+            ));
+        });
 }
 
 fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/slkscr.ttf");
     // Root node
     commands
         .spawn(Node {
@@ -40,6 +305,61 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         })
         .with_children(|parent| {
+            // Player vitals and cooldowns, top left
+            parent
+                .spawn(Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(20.0),
+                    left: Val::Px(20.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_text(parent, &font, "HP", HealthText);
+                    spawn_bar(parent, 160.0, Color::srgb(0.85, 0.2, 0.2), HealthBar);
+                    spawn_text(parent, &font, "Mass", MassText);
+                    spawn_text(parent, &font, "Dash", DashText);
+                    spawn_bar(parent, 120.0, Color::srgb(0.6, 0.85, 1.0), DashBar);
+                    parent
+                        .spawn((
+                            Node {
+                                flex_direction: FlexDirection::Column,
+                                ..default()
+                            },
+                            ShurikenHud,
+                        ))
+                        .with_children(|parent| {
+                            spawn_text(parent, &font, "Shuriken", ());
+                            spawn_bar(parent, 120.0, Color::srgb(0.9, 0.3, 0.35), ShurikenBar);
+                        });
+                });
+            // Run progress, top right
+            parent
+                .spawn(Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(20.0),
+                    right: Val::Px(20.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::End,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_text(parent, &font, "Level 1", LevelText);
+                    spawn_text(parent, &font, "00:00", TimeText);
+                    spawn_text(parent, &font, "Kills 0", KillText);
+                });
+            // Active effects, top centre
+            parent
+                .spawn(Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(20.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_text(parent, &font, "", SugarRushText);
+                });
             parent
                 .spawn(Node {
                     width: Val::Percent(100.0),
@@ -50,10 +370,18 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                     ..default()
                 })
                 .with_children(|parent| {
+                    parent.spawn((
+                        Node {
+                            margin: UiRect::bottom(Val::Px(8.0)),
+                            flex_direction: FlexDirection::Row,
+                            ..default()
+                        },
+                        UpgradeIconRow,
+                    ));
                     parent.spawn((
                         Text::new("XP"),
                         TextFont {
-                            font: asset_server.load("fonts/slkscr.ttf"),
+                            font: font.clone(),
                             font_size: 24.0,
                             ..default()
                         },
@@ -0,0 +1,41 @@
+//! Ids of the upgrade cards, shared by the card list, the code applying them and evolution
+//! requirements so none of them depend on raw numbers.
+
+pub const CHESTNUT_SHIELD: u32 = 0;
+pub const RATE_OF_FIRE: u32 = 1;
+pub const PROJECTILE_SPEED: u32 = 2;
+pub const DAMAGE: u32 = 3;
+pub const ACCELERATION: u32 = 4;
+pub const SHIELD_DAMAGE: u32 = 5;
+pub const SHIELD_ROTATION_SPEED: u32 = 6;
+pub const FREEZE_CHANCE: u32 = 7;
+pub const FREEZE_DURATION: u32 = 8;
+pub const FIRE_CHANCE: u32 = 9;
+pub const FIRE_DURATION: u32 = 10;
+pub const FIRE_DAMAGE: u32 = 11;
+pub const FLASH_FREEZE: u32 = 12;
+pub const FLASH_FREEZE_DAMAGE: u32 = 13;
+pub const FREEZER_BURN: u32 = 14;
+pub const FREEZER_BURN_DURATION: u32 = 15;
+pub const FREEZER_BURN_MULTIPLIER: u32 = 16;
+pub const PIERCE: u32 = 17;
+pub const SHIELDS_APPLY_EFFECTS: u32 = 18;
+pub const SUGAR_RUSH_DAMAGE: u32 = 19;
+pub const KNOCKBACK_STRENGTH: u32 = 20;
+pub const DASH_CHARGES: u32 = 21;
+pub const DASH_DISTANCE: u32 = 22;
+pub const FROST_TRAIL: u32 = 23;
+pub const CANDY_CANE_SHURIKENS: u32 = 24;
+pub const CRITICAL_CHANCE: u32 = 25;
+pub const CRITICAL_DAMAGE: u32 = 26;
+pub const CHILL_CHANCE: u32 = 27;
+pub const CHILL_DURATION: u32 = 28;
+pub const SHOCK_CHANCE: u32 = 29;
+pub const SHOCK_CHAINS: u32 = 30;
+pub const PEPPERMINT_POISON: u32 = 31;
+pub const POISON_POTENCY: u32 = 32;
+pub const SUPERCONDUCT: u32 = 33;
+pub const TOXIC_FLARE: u32 = 34;
+pub const LUCKY_STAR: u32 = 35;
+pub const SWEET_TOOTH: u32 = 36;
+pub const LINGERING_PEPPERMINT: u32 = 37;